@@ -0,0 +1,157 @@
+//! Comparison guards.
+
+/// Version of [`guard!`] checking that two expressions are equal.
+///
+/// Each operand is evaluated exactly once. If they are not equal, the current function
+/// early-returns with a [`GuardError`] capturing both operands and the operator, or with the
+/// user-provided error passed as third argument.
+///
+/// ```rust
+/// use try_guard::{guard_eq, GuardError};
+///
+/// fn foo(a: u32, b: u32) -> Result<(), GuardError> {
+///   guard_eq!(a, b);
+///   Ok(())
+/// }
+///
+/// let err = foo(1, 2).unwrap_err();
+/// assert_eq!(err.expr(), "a == b");
+/// assert_eq!(err.operator(), Some("=="));
+/// assert_eq!(err.operands()[0].value(), Some("1"));
+/// assert_eq!(err.operands()[1].value(), Some("2"));
+/// ```
+///
+/// [`guard!`]: crate::guard
+/// [`GuardError`]: crate::GuardError
+#[macro_export]
+macro_rules! guard_eq {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left == $right] ==, $left, $right)
+  };
+
+  ($left:expr, $right:expr, $err:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left == $right] ==, $left, $right, $err)
+  };
+}
+
+/// Version of [`guard!`] checking that two expressions are not equal.
+///
+/// See [`guard_eq!`] for further details.
+///
+/// [`guard!`]: crate::guard
+/// [`guard_eq!`]: crate::guard_eq
+#[macro_export]
+macro_rules! guard_ne {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left != $right] !=, $left, $right)
+  };
+
+  ($left:expr, $right:expr, $err:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left != $right] !=, $left, $right, $err)
+  };
+}
+
+/// Version of [`guard!`] checking that an expression is less than another.
+///
+/// See [`guard_eq!`] for further details.
+///
+/// [`guard!`]: crate::guard
+/// [`guard_eq!`]: crate::guard_eq
+#[macro_export]
+macro_rules! guard_lt {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left < $right] <, $left, $right)
+  };
+
+  ($left:expr, $right:expr, $err:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left < $right] <, $left, $right, $err)
+  };
+}
+
+/// Version of [`guard!`] checking that an expression is less than or equal to another.
+///
+/// See [`guard_eq!`] for further details.
+///
+/// [`guard!`]: crate::guard
+/// [`guard_eq!`]: crate::guard_eq
+#[macro_export]
+macro_rules! guard_le {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left <= $right] <=, $left, $right)
+  };
+
+  ($left:expr, $right:expr, $err:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left <= $right] <=, $left, $right, $err)
+  };
+}
+
+/// Version of [`guard!`] checking that an expression is greater than another.
+///
+/// See [`guard_eq!`] for further details.
+///
+/// [`guard!`]: crate::guard
+/// [`guard_eq!`]: crate::guard_eq
+#[macro_export]
+macro_rules! guard_gt {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left > $right] >, $left, $right)
+  };
+
+  ($left:expr, $right:expr, $err:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left > $right] >, $left, $right, $err)
+  };
+}
+
+/// Version of [`guard!`] checking that an expression is greater than or equal to another.
+///
+/// See [`guard_eq!`] for further details.
+///
+/// [`guard!`]: crate::guard
+/// [`guard_eq!`]: crate::guard_eq
+#[macro_export]
+macro_rules! guard_ge {
+  ($left:expr, $right:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left >= $right] >=, $left, $right)
+  };
+
+  ($left:expr, $right:expr, $err:expr $(,)?) => {
+    $crate::__guard_cmp!({$crate::__guard_fail!} [$left >= $right] >=, $left, $right, $err)
+  };
+}
+
+/// Compare two operands, evaluating each exactly once, and pass the failure to the failure
+/// callback — the first argument — if the comparison is `false`.
+///
+/// The second argument is the whole comparison, used to report the failed predicate.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guard_cmp {
+  ({$($fail:tt)+} [$($expr:tt)+] $op:tt, $left:expr, $right:expr) => {
+    match (&$left, &$right) {
+      (left, right) => {
+        if !(*left $op *right) {
+          $($fail)+($crate::GuardError::new(
+            stringify!($($expr)+),
+            file!(),
+            line!(),
+            column!(),
+            module_path!(),
+          )
+          .with_operator(stringify!($op))
+          .with_operand(stringify!($left), $crate::__guard_capture!(left))
+          .with_operand(stringify!($right), $crate::__guard_capture!(right)))
+        }
+      }
+    }
+  };
+
+  ({$($fail:tt)+} [$($expr:tt)+] $op:tt, $left:expr, $right:expr, $err:expr) => {
+    match (&$left, &$right) {
+      (left, right) => {
+        if !(*left $op *right) {
+          $($fail)+($err)
+        }
+      }
+    }
+  };
+}
@@ -0,0 +1,85 @@
+//! Guards for const contexts.
+
+/// Version of [`guard!`] usable in `const fn`s.
+///
+/// [`guard!`] can’t be used in const contexts, as it relies on a trait to build the failure.
+/// `const_guard!(cond)` early-returns `None` if `cond` is `false`, and `const_guard!(cond, err)`
+/// early-returns `Err(err)` — without converting `err`, as [`From`] isn’t usable in const contexts
+/// either.
+///
+/// ```rust
+/// use try_guard::const_guard;
+///
+/// const fn checked_div(a: u32, b: u32) -> Option<u32> {
+///   const_guard!(b != 0);
+///   Some(a / b)
+/// }
+///
+/// const fn nth_power_of_two(n: u32) -> Result<u64, &'static str> {
+///   const_guard!(n < 64, "too large");
+///   Ok(1 << n)
+/// }
+///
+/// const HALF: Option<u32> = checked_div(10, 2);
+/// const LARGE: Result<u64, &str> = nth_power_of_two(64);
+///
+/// assert_eq!(HALF, Some(5));
+/// assert_eq!(checked_div(1, 0), None);
+/// assert_eq!(LARGE, Err("too large"));
+/// ```
+///
+/// [`guard!`]: crate::guard
+#[macro_export]
+macro_rules! const_guard {
+  ($e:expr $(,)?) => {
+    if !$e {
+      return ::core::option::Option::None;
+    }
+  };
+
+  ($e:expr, $err:expr $(,)?) => {
+    if !$e {
+      return ::core::result::Result::Err($err);
+    }
+  };
+}
+
+/// Compile-time guard.
+///
+/// `static_guard!(cond)` fails compilation if `cond`, a constant expression, is `false`. A custom
+/// message can be passed as second argument. It can be used wherever items can: at the top level
+/// of a module or in a function body.
+///
+/// ```rust
+/// use try_guard::static_guard;
+///
+/// const BUCKETS: usize = 16;
+/// const TABLE: [u8; 4] = [1, 2, 4, 8];
+///
+/// static_guard!(BUCKETS.is_power_of_two());
+/// static_guard!(TABLE.len() <= BUCKETS, "TABLE doesn’t fit in the buckets");
+/// ```
+///
+/// ```compile_fail
+/// use try_guard::static_guard;
+///
+/// const BUCKETS: usize = 12;
+///
+/// static_guard!(BUCKETS.is_power_of_two());
+/// ```
+///
+/// Because it’s an item, it can’t refer to generic parameters. Use an inline `const` block with
+/// [`assert!`] to check const generic parameters.
+#[macro_export]
+macro_rules! static_guard {
+  ($e:expr $(,)?) => {
+    const _: () = ::core::assert!(
+      $e,
+      ::core::concat!("static guard failed: ", ::core::stringify!($e))
+    );
+  };
+
+  ($e:expr, $msg:literal $(,)?) => {
+    const _: () = ::core::assert!($e, $msg);
+  };
+}
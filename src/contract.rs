@@ -0,0 +1,112 @@
+//! Support for the contract attributes.
+
+use std::sync::{PoisonError, RwLock};
+
+use crate::GuardError;
+
+/// Reference to the value returned by a function with postconditions.
+///
+/// Postconditions are only checked when the function succeeds: [`Succeeded`] is implemented on
+/// `Output` for [`Option`] and [`Result`], giving the wrapped value if any, and
+/// [`SucceededFallback`] on `&Output` for any other type, giving the value itself. Autoref-based
+/// method resolution picks the former when possible.
+pub struct Output<'a, T: ?Sized>(pub &'a T);
+
+pub trait Succeeded<'a> {
+  type Value: ?Sized;
+
+  fn succeeded(&self) -> Option<&'a Self::Value>;
+}
+
+impl<'a, T> Succeeded<'a> for Output<'a, Option<T>> {
+  type Value = T;
+
+  fn succeeded(&self) -> Option<&'a T> {
+    self.0.as_ref()
+  }
+}
+
+impl<'a, T, E> Succeeded<'a> for Output<'a, Result<T, E>> {
+  type Value = T;
+
+  fn succeeded(&self) -> Option<&'a T> {
+    self.0.as_ref().ok()
+  }
+}
+
+pub trait SucceededFallback<'a> {
+  type Value: ?Sized;
+
+  fn succeeded(&self) -> Option<&'a Self::Value>;
+}
+
+impl<'a, T> SucceededFallback<'a> for &Output<'a, T>
+where
+  T: ?Sized,
+{
+  type Value = T;
+
+  fn succeeded(&self) -> Option<&'a T> {
+    Some(self.0)
+  }
+}
+
+/// Call the closure wrapping the body of a function with a contract.
+///
+/// Taking it as an [`FnOnce`] allows it to give back borrows of the references it moved.
+pub fn call_once<F, R>(f: F) -> R
+where
+  F: FnOnce() -> R,
+{
+  f()
+}
+
+/// Report an invariant violated by an infallible method by panicking.
+pub fn invariant_panic(error: GuardError) -> ! {
+  panic!("invariant violated: {}", error)
+}
+
+/// Hook set with [`set_invariant_log_hook`].
+type LogHook = Box<dyn Fn(&GuardError) + Send + Sync>;
+
+static LOG_HOOK: RwLock<Option<LogHook>> = RwLock::new(None);
+
+/// Set the function invariants violated by infallible methods with `policy = log` are reported
+/// to.
+///
+/// By default, they’re printed on the standard error. This is typically used to forward them to
+/// a logging framework.
+///
+/// ```rust
+/// use try_guard::{invariant, set_invariant_log_hook};
+///
+/// struct Gauge {
+///   value: u8,
+/// }
+///
+/// #[invariant(self.value <= 100, policy = log)]
+/// impl Gauge {
+///   fn set(&mut self, value: u8) {
+///     self.value = value;
+///   }
+/// }
+///
+/// set_invariant_log_hook(|error| println!("warning: {}", error.expr()));
+///
+/// // prints “warning: self.value <= 100”
+/// Gauge { value: 0 }.set(142);
+/// ```
+pub fn set_invariant_log_hook<F>(hook: F)
+where
+  F: Fn(&GuardError) + Send + Sync + 'static,
+{
+  *LOG_HOOK.write().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(hook));
+}
+
+/// Report an invariant violated by an infallible method to the log hook.
+pub fn invariant_log(error: GuardError) {
+  match *LOG_HOOK.read().unwrap_or_else(PoisonError::into_inner) {
+    Some(ref hook) => hook(&error),
+    None => eprintln!("invariant violated: {}", error),
+  }
+}
@@ -0,0 +1,141 @@
+//! Guards for loops, labeled blocks and [`ControlFlow`].
+
+use std::ops::ControlFlow;
+
+use crate::{GuardFailed, GuardTarget};
+
+/// Version of [`guard!`] continuing a loop.
+///
+/// `guard_continue!(cond)` skips to the next iteration of the innermost loop if `cond` is `false`,
+/// and `guard_continue!(cond, 'label)` to the next iteration of the loop labeled `'label`.
+///
+/// ```rust
+/// use try_guard::guard_continue;
+///
+/// let mut sum = 0;
+///
+/// 'rows: for row in &[[1, 2], [-3, 4], [5, 6]] {
+///   for x in row {
+///     guard_continue!(*x > 0, 'rows);
+///     guard_continue!(x % 2 == 0);
+///     sum += x;
+///   }
+/// }
+///
+/// assert_eq!(sum, 8);
+/// ```
+///
+/// [`guard!`]: crate::guard
+#[macro_export]
+macro_rules! guard_continue {
+  ($e:expr $(,)?) => {
+    if !$e {
+      continue;
+    }
+  };
+
+  ($e:expr, $l:lifetime $(,)?) => {
+    if !$e {
+      continue $l;
+    }
+  };
+}
+
+/// Version of [`guard!`] breaking out of a loop or a labeled block.
+///
+/// If `cond` is `false`:
+///
+///   - `guard_break!(cond)` breaks out of the innermost loop.
+///   - `guard_break!(cond, value)` breaks out of the innermost `loop` with `value`.
+///   - `guard_break!(cond, 'label)` breaks out of the loop or block labeled `'label`.
+///   - `guard_break!(cond, 'label, value)` breaks out of the `loop` or block labeled `'label` with
+///     `value`.
+///
+/// ```rust
+/// use try_guard::guard_break;
+///
+/// fn parse(s: &str) -> Result<u32, &'static str> {
+///   'parse: {
+///     guard_break!(!s.is_empty(), 'parse, Err("empty"));
+///     guard_break!(s.len() <= 9, 'parse, Err("too long"));
+///     s.parse().map_err(|_| "not a number")
+///   }
+/// }
+///
+/// assert_eq!(parse("42"), Ok(42));
+/// assert_eq!(parse(""), Err("empty"));
+/// assert_eq!(parse("1234567890"), Err("too long"));
+/// ```
+///
+/// [`guard!`]: crate::guard
+#[macro_export]
+macro_rules! guard_break {
+  ($e:expr $(,)?) => {
+    if !$e {
+      break;
+    }
+  };
+
+  ($e:expr, $l:lifetime $(,)?) => {
+    if !$e {
+      break $l;
+    }
+  };
+
+  ($e:expr, $l:lifetime, $v:expr $(,)?) => {
+    if !$e {
+      break $l $v;
+    }
+  };
+
+  ($e:expr, $v:expr $(,)?) => {
+    if !$e {
+      break $v;
+    }
+  };
+}
+
+/// Guards in [`ControlFlow`]-returning closures — e.g. with [`Iterator::try_for_each`] — break
+/// with the failure.
+///
+/// ```rust
+/// use std::ops::ControlFlow;
+/// use try_guard::guard;
+///
+/// let flow = [1, 2, -3, 4].iter().try_for_each(|x| {
+///   guard!(*x > 0, *x);
+///   ControlFlow::Continue(())
+/// });
+///
+/// assert_eq!(flow, ControlFlow::Break(-3));
+/// ```
+impl<B, C, F> GuardTarget<F> for ControlFlow<B, C>
+where
+  B: From<F>,
+{
+  fn guard_failed(failure: F) -> Self {
+    ControlFlow::Break(failure.into())
+  }
+}
+
+/// Guards without error in `ControlFlow<(), C>`-returning closures break with `()`.
+///
+/// ```rust
+/// use std::ops::ControlFlow;
+/// use try_guard::guard;
+///
+/// let mut seen = Vec::new();
+/// let flow = [1, 2, -3, 4].iter().try_for_each(|x| {
+///   guard!(*x > 0);
+///   seen.push(*x);
+///   ControlFlow::Continue(())
+/// });
+///
+/// assert_eq!(flow, ControlFlow::Break(()));
+/// assert_eq!(seen, [1, 2]);
+/// ```
+impl<C> GuardTarget<GuardFailed> for ControlFlow<(), C> {
+  fn guard_failed(_: GuardFailed) -> Self {
+    ControlFlow::Break(())
+  }
+}
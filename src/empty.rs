@@ -0,0 +1,106 @@
+//! Early-returning empty values.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
+
+use crate::GuardTarget;
+
+/// Types with an empty value [`guard!`] can early-return with.
+///
+/// Every [`GuardEmpty`] type is a [`GuardTarget`] dropping the failure, so that [`guard!`] in a
+/// function returning a collection gives an empty collection, and in a function returning a
+/// [`bool`] gives `false`:
+///
+/// ```rust
+/// use try_guard::guard;
+///
+/// fn divisors(n: u32) -> Vec<u32> {
+///   guard!(n > 0);
+///   (1..=n).filter(|d| n % d == 0).collect()
+/// }
+///
+/// fn is_palindrome(s: &str) -> bool {
+///   guard!(s.is_ascii());
+///   s.bytes().eq(s.bytes().rev())
+/// }
+///
+/// assert_eq!(divisors(6), [1, 2, 3, 6]);
+/// assert!(divisors(0).is_empty());
+/// assert!(is_palindrome("kayak"));
+/// assert!(!is_palindrome("été"));
+/// ```
+///
+/// Implement it for your own types to get the same behavior.
+///
+/// [`guard!`]: crate::guard
+pub trait GuardEmpty {
+  /// Empty value.
+  fn empty() -> Self;
+}
+
+impl<T, F> GuardTarget<F> for T
+where
+  T: GuardEmpty,
+{
+  fn guard_failed(_: F) -> Self {
+    T::empty()
+  }
+}
+
+impl GuardEmpty for () {
+  fn empty() -> Self {}
+}
+
+impl GuardEmpty for bool {
+  fn empty() -> Self {
+    false
+  }
+}
+
+impl GuardEmpty for String {
+  fn empty() -> Self {
+    String::new()
+  }
+}
+
+impl<T> GuardEmpty for Vec<T> {
+  fn empty() -> Self {
+    Vec::new()
+  }
+}
+
+impl<T> GuardEmpty for VecDeque<T> {
+  fn empty() -> Self {
+    VecDeque::new()
+  }
+}
+
+impl<K, V, S> GuardEmpty for HashMap<K, V, S>
+where
+  S: BuildHasher + Default,
+{
+  fn empty() -> Self {
+    HashMap::default()
+  }
+}
+
+impl<T, S> GuardEmpty for HashSet<T, S>
+where
+  S: BuildHasher + Default,
+{
+  fn empty() -> Self {
+    HashSet::default()
+  }
+}
+
+impl<K, V> GuardEmpty for BTreeMap<K, V> {
+  fn empty() -> Self {
+    BTreeMap::new()
+  }
+}
+
+impl<T> GuardEmpty for BTreeSet<T> {
+  fn empty() -> Self {
+    BTreeSet::new()
+  }
+}
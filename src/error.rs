@@ -0,0 +1,235 @@
+//! Errors produced by failed guards.
+
+use std::error::Error;
+use std::fmt;
+use std::panic::Location;
+
+use crate::path::{Path, PathSegment, PrefixPath};
+
+/// Error produced by a failed [`guard!`] in a function returning a [`Result`].
+///
+/// [`guard!`]: crate::guard
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GuardFailed;
+
+impl fmt::Display for GuardFailed {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("guard failed")
+  }
+}
+
+impl Error for GuardFailed {}
+
+/// Error produced by a failed [`guard_report!`], capturing the failed predicate and the location of
+/// the call site.
+///
+/// [`guard_report!`]: crate::guard_report
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GuardError {
+  expr: &'static str,
+  file: &'static str,
+  line: u32,
+  column: u32,
+  module_path: Option<&'static str>,
+  details: Option<Box<Details>>,
+  path: Path,
+}
+
+/// Details of a failed predicate, boxed to keep [`GuardError`] small, as it’s typically returned in
+/// a [`Result`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+struct Details {
+  operator: Option<&'static str>,
+  operands: Vec<Operand>,
+  reason: Option<String>,
+}
+
+impl GuardError {
+  /// Create a [`GuardError`] for the predicate `expr` failing at the given location.
+  ///
+  /// You shouldn’t need this directly: [`guard_report!`] fills everything in for you.
+  ///
+  /// [`guard_report!`]: crate::guard_report
+  pub fn new(
+    expr: &'static str,
+    file: &'static str,
+    line: u32,
+    column: u32,
+    module_path: &'static str,
+  ) -> Self {
+    GuardError {
+      expr,
+      file,
+      line,
+      column,
+      module_path: Some(module_path),
+      details: None,
+      path: Path::new(),
+    }
+  }
+
+  /// Create a [`GuardError`] for the predicate `expr` failing at the location of the caller,
+  /// without module path.
+  #[track_caller]
+  pub(crate) fn at_caller(expr: &'static str) -> Self {
+    let location = Location::caller();
+
+    GuardError {
+      expr,
+      file: location.file(),
+      line: location.line(),
+      column: location.column(),
+      module_path: None,
+      details: None,
+      path: Path::new(),
+    }
+  }
+
+  /// Replace the failed predicate.
+  pub fn with_expr(mut self, expr: &'static str) -> Self {
+    self.expr = expr;
+    self
+  }
+
+  /// Set the comparison operator of the failed predicate.
+  pub fn with_operator(mut self, operator: &'static str) -> Self {
+    self.details_mut().operator = Some(operator);
+    self
+  }
+
+  /// Add an operand of the failed predicate, with its [`Debug`](fmt::Debug) representation if
+  /// any.
+  pub fn with_operand(mut self, expr: &'static str, value: Option<String>) -> Self {
+    self.details_mut().operands.push(Operand { expr, value });
+    self
+  }
+
+  /// Set the reason of the failure, such as the description of the failed part of a
+  /// [`Predicate`](crate::Predicate).
+  pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+    self.details_mut().reason = Some(reason.into());
+    self
+  }
+
+  fn details_mut(&mut self) -> &mut Details {
+    self.details.get_or_insert_with(Default::default)
+  }
+
+  /// Stringified predicate that failed.
+  pub fn expr(&self) -> &'static str {
+    self.expr
+  }
+
+  /// File the guard lives in.
+  pub fn file(&self) -> &'static str {
+    self.file
+  }
+
+  /// Line the guard lives at.
+  pub fn line(&self) -> u32 {
+    self.line
+  }
+
+  /// Column the guard lives at.
+  pub fn column(&self) -> u32 {
+    self.column
+  }
+
+  /// Path of the module the guard lives in, if known.
+  ///
+  /// Functions checking predicates — [`Guarded::try_new`](crate::Guarded::try_new) and
+  /// [`Predicate::verify`](crate::Predicate::verify) — only know the file, line and column of their
+  /// caller: they give `None`.
+  pub fn module_path(&self) -> Option<&'static str> {
+    self.module_path
+  }
+
+  /// Comparison operator of the failed predicate, if it’s a comparison.
+  pub fn operator(&self) -> Option<&'static str> {
+    self.details.as_ref()?.operator
+  }
+
+  /// Operands of the failed predicate, captured at runtime.
+  pub fn operands(&self) -> &[Operand] {
+    self
+      .details
+      .as_ref()
+      .map_or(&[], |details| &details.operands)
+  }
+
+  /// Reason of the failure, if any.
+  ///
+  /// Failures of [`Predicate`](crate::Predicate)s checked with `value => predicate` give the
+  /// description of the part of the predicate the value doesn’t satisfy.
+  pub fn reason(&self) -> Option<&str> {
+    self.details.as_ref()?.reason.as_deref()
+  }
+
+  /// Path of the validated value the guard failed on, if validated in a [`ValidationContext`].
+  ///
+  /// [`ValidationContext`]: crate::ValidationContext
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl fmt::Display for GuardError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if !self.path.is_empty() {
+      write!(f, "{}: ", self.path)?;
+    }
+
+    write!(
+      f,
+      "guard failed: `{}` at {}:{}:{}",
+      self.expr, self.file, self.line, self.column
+    )?;
+
+    if let Some(reason) = self.reason() {
+      write!(f, "\n  expected {}", reason)?;
+    }
+
+    for operand in self.operands() {
+      write!(f, "\n  {}", operand)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Error for GuardError {}
+
+impl PrefixPath for GuardError {
+  fn prefix_path(mut self, prefix: &[PathSegment]) -> Self {
+    self.path = self.path.prefix_path(prefix);
+    self
+  }
+}
+
+/// Operand of a failed predicate, captured by a [`GuardError`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Operand {
+  expr: &'static str,
+  value: Option<String>,
+}
+
+impl Operand {
+  /// Stringified operand.
+  pub fn expr(&self) -> &'static str {
+    self.expr
+  }
+
+  /// [`Debug`](fmt::Debug) representation of the operand, if it implements it.
+  pub fn value(&self) -> Option<&str> {
+    self.value.as_deref()
+  }
+}
+
+impl fmt::Display for Operand {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.value {
+      Some(ref value) => write!(f, "`{}` = {}", self.expr, value),
+      None => write!(f, "`{}`", self.expr),
+    }
+  }
+}
@@ -0,0 +1,166 @@
+//! Extension traits exposing guards as methods.
+
+/// Guards on [`bool`]s, as methods.
+///
+/// These are the method counterparts of [`verify!`], [`verify_or!`] and [`verify_or_else!`],
+/// which are implemented in terms of them.
+///
+/// ```rust
+/// use try_guard::BoolExt;
+///
+/// fn half(x: u32) -> Option<u32> {
+///   (x % 2 == 0).guard()?;
+///   Some(x / 2)
+/// }
+///
+/// fn check_small(x: u32) -> Result<u32, String> {
+///   (x < 10).guard_or_else(|| format!("{} is too big", x))?;
+///   Ok(x)
+/// }
+///
+/// assert_eq!(half(4), Some(2));
+/// assert_eq!(half(3), None);
+/// assert_eq!(check_small(12), Err("12 is too big".to_owned()));
+/// ```
+///
+/// [`verify!`]: crate::verify
+/// [`verify_or!`]: crate::verify_or
+/// [`verify_or_else!`]: crate::verify_or_else
+pub trait BoolExt {
+  /// `Some(())` if `true`, `None` otherwise.
+  fn guard(self) -> Option<()>;
+
+  /// `Some(f())` if `true`, `None` otherwise.
+  fn guard_then<T, F>(self, f: F) -> Option<T>
+  where
+    F: FnOnce() -> T;
+
+  /// `Ok(())` if `true`, `Err(err)` otherwise.
+  fn guard_or<E>(self, err: E) -> Result<(), E>;
+
+  /// `Ok(())` if `true`, `Err(f())` otherwise.
+  fn guard_or_else<E, F>(self, f: F) -> Result<(), E>
+  where
+    F: FnOnce() -> E;
+}
+
+impl BoolExt for bool {
+  fn guard(self) -> Option<()> {
+    if self {
+      Some(())
+    } else {
+      None
+    }
+  }
+
+  fn guard_then<T, F>(self, f: F) -> Option<T>
+  where
+    F: FnOnce() -> T,
+  {
+    self.guard().map(|()| f())
+  }
+
+  fn guard_or<E>(self, err: E) -> Result<(), E> {
+    self.guard().ok_or(err)
+  }
+
+  fn guard_or_else<E, F>(self, f: F) -> Result<(), E>
+  where
+    F: FnOnce() -> E,
+  {
+    self.guard().ok_or_else(f)
+  }
+}
+
+/// Guards on [`Option`]s, as methods.
+///
+/// ```rust
+/// use try_guard::OptionExt;
+///
+/// fn even(x: Option<u32>) -> Result<u32, &'static str> {
+///   x.guard_or(|x| x % 2 == 0, "not even")
+/// }
+///
+/// assert_eq!(Some(4).guard(|x| x % 2 == 0), Some(4));
+/// assert_eq!(Some(3).guard(|x| x % 2 == 0), None);
+/// assert_eq!(even(Some(3)), Err("not even"));
+/// assert_eq!(even(None), Err("not even"));
+/// ```
+pub trait OptionExt<T> {
+  /// Keep the value if it satisfies `f`.
+  fn guard<F>(self, f: F) -> Option<T>
+  where
+    F: FnOnce(&T) -> bool;
+
+  /// `Ok(value)` if there’s a value satisfying `f`, `Err(err)` otherwise.
+  fn guard_or<E, F>(self, f: F, err: E) -> Result<T, E>
+  where
+    F: FnOnce(&T) -> bool;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+  fn guard<F>(self, f: F) -> Option<T>
+  where
+    F: FnOnce(&T) -> bool,
+  {
+    self.filter(f)
+  }
+
+  fn guard_or<E, F>(self, f: F, err: E) -> Result<T, E>
+  where
+    F: FnOnce(&T) -> bool,
+  {
+    self.filter(f).ok_or(err)
+  }
+}
+
+/// Guards on [`Result`]s, as methods.
+///
+/// ```rust
+/// use try_guard::ResultExt;
+///
+/// fn port(s: &str) -> Result<u16, String> {
+///   s.parse::<u16>()
+///     .map_err(|e| e.to_string())
+///     .ensure(|port| *port >= 1024, "reserved port".to_owned())
+/// }
+///
+/// assert_eq!(port("8080"), Ok(8080));
+/// assert_eq!(port("80"), Err("reserved port".to_owned()));
+/// assert!(port("http").is_err());
+/// ```
+pub trait ResultExt<T, E> {
+  /// Replace a value not satisfying `f` with `Err(err)`.
+  fn ensure<F>(self, f: F, err: E) -> Result<T, E>
+  where
+    F: FnOnce(&T) -> bool;
+
+  /// Replace a value not satisfying `f` with `Err(g(value))`.
+  fn ensure_else<F, G>(self, f: F, g: G) -> Result<T, E>
+  where
+    F: FnOnce(&T) -> bool,
+    G: FnOnce(T) -> E;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+  fn ensure<F>(self, f: F, err: E) -> Result<T, E>
+  where
+    F: FnOnce(&T) -> bool,
+  {
+    self.ensure_else(f, |_| err)
+  }
+
+  fn ensure_else<F, G>(self, f: F, g: G) -> Result<T, E>
+  where
+    F: FnOnce(&T) -> bool,
+    G: FnOnce(T) -> E,
+  {
+    let value = self?;
+
+    if f(&value) {
+      Ok(value)
+    } else {
+      Err(g(value))
+    }
+  }
+}
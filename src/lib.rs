@@ -31,30 +31,46 @@
 //! function — that helps early-return from a function if a predicate is `false`:
 //!
 //! ```rust
-//! # #![cfg_attr(feature = "test-nightly", feature(try_trait))]
-//! # #[cfg(feature = "test-nightly")] mod lol {
 //! use try_guard::guard;
 //!
 //! fn foo(cond: bool) -> Option<i32> {
 //!   guard!(cond);
 //!   Some(42)
 //! }
-//! # }
+//! ```
+//!
+//! The same macro works in functions returning a [`Result`], as long as the error type can be
+//! built from [`GuardFailed`]:
+//!
+//! ```rust
+//! use try_guard::{guard, GuardFailed};
+//!
+//! #[derive(Debug)]
+//! enum MyError {
+//!   GuardFailed
+//! }
+//!
+//! impl From<GuardFailed> for MyError {
+//!   fn from(_: GuardFailed) -> Self {
+//!     MyError::GuardFailed
+//!   }
+//! }
+//!
+//! fn foo(cond: bool) -> Result<i32, MyError> {
+//!   guard!(cond);
+//!   Ok(42)
+//! }
 //! ```
 //!
 //! ## Custom guard types
 //!
-//! This crate also allows you to _guard_ to anything that implements [`Try<Error = NoneError>`] or
-//! `From<NoneError>` (nightly only).
+//! This crate also allows you to _guard_ to anything that implements [`GuardTarget`], which is
+//! what [`guard!`] uses to build the value it early-returns with.
 //!
 //! For instance, the following works:
 //!
 //! ```rust
-//! # #![cfg_attr(feature = "test-nightly", feature(try_trait))]
-//! # #[cfg(feature = "test-nightly")] mod lol {
-//! use std::ops::Try;
-//! use std::option::NoneError;
-//! use try_guard::guard;
+//! use try_guard::{guard, GuardFailed, GuardTarget};
 //!
 //! #[derive(Clone, Debug, Eq, PartialEq)]
 //! enum MyGuard<T> {
@@ -72,30 +88,51 @@
 //!   }
 //! }
 //!
-//! impl<T> Try for MyGuard<T> {
-//!   type Ok = T;
-//!
-//!   type Error = NoneError;
-//!
-//!   fn from_error(_: Self::Error) -> Self {
+//! impl<T> GuardTarget for MyGuard<T> {
+//!   fn guard_failed(_: GuardFailed) -> Self {
 //!     MyGuard::none()
 //!   }
+//! }
 //!
-//!   fn from_ok(x: Self::Ok) -> Self {
-//!     MyGuard::new(x)
-//!   }
+//! fn foo(cond: bool) -> MyGuard<i32> {
+//!   guard!(cond);
+//!   MyGuard::new(42)
+//! }
+//!
+//! fn main() {
+//!   assert_eq!(foo(false), MyGuard::Nothing);
+//! }
+//! ```
 //!
-//!   fn into_result(self) -> Result<Self::Ok, Self::Error> {
-//!     match self {
-//!       MyGuard::Just(x) => Ok(x),
-//!       MyGuard::Nothing => Err(NoneError)
-//!     }
+//! Types with an obvious empty value — such as collections, `()` and `bool` — can implement
+//! [`GuardEmpty`] instead, so that [`guard!`] early-returns with it.
+//!
+//! With the `nightly` feature, [`guard_try!`] is a version of [`guard!`] early-exiting through the
+//! [`?`] operator instead, so that it works in (heterogeneous) `try` blocks too. The residual is a
+//! [`GuardResidual`], so types have to implement [`FromResidual<GuardResidual>`] instead of
+//! [`GuardTarget`]:
+//!
+//! ```rust
+//! # #![cfg_attr(feature = "nightly", feature(try_trait_v2, try_trait_v2_residual))]
+//! # #[cfg(feature = "nightly")] mod lol {
+//! use std::ops::FromResidual;
+//! use try_guard::{guard_try, GuardResidual};
+//!
+//! #[derive(Clone, Debug, Eq, PartialEq)]
+//! enum MyGuard<T> {
+//!   Just(T),
+//!   Nothing
+//! }
+//!
+//! impl<T> FromResidual<GuardResidual> for MyGuard<T> {
+//!   fn from_residual(_: GuardResidual) -> Self {
+//!     MyGuard::Nothing
 //!   }
 //! }
 //!
 //! fn foo(cond: bool) -> MyGuard<i32> {
-//!   guard!(cond);
-//!   MyGuard::new(42)
+//!   guard_try!(cond);
+//!   MyGuard::Just(42)
 //! }
 //!
 //! fn main() {
@@ -106,6 +143,127 @@
 //!
 //! ## More control on the error type
 //!
+//! [`guard!`] accepts an optional error expression. If the predicate is `false`, the error is
+//! converted with [`From`] — just like [`?`] does — and early-returned. The expression is only
+//! evaluated if the predicate is `false`.
+//!
+//! ```rust
+//! use try_guard::guard;
+//!
+//! fn foo(cond: bool) -> Result<u32, String> {
+//!   guard!(cond, "bad condition");
+//!   Ok(123)
+//! }
+//!
+//! assert_eq!(foo(false), Err("bad condition".to_owned()));
+//! ```
+//!
+//! ## Arbitrary early exits
+//!
+//! Sometimes, the early exit is not an error. [`guard!`] accepts an `else` block that is run if the
+//! predicate is `false`. That block must diverge — i.e. `return`, `continue`, `break`, panic, etc.
+//! — or the code won’t compile.
+//!
+//! ```rust
+//! use try_guard::guard;
+//!
+//! fn sum_even(xs: &[u32]) -> u32 {
+//!   let mut sum = 0;
+//!
+//!   for x in xs {
+//!     guard!(x % 2 == 0 else { continue });
+//!     sum += x;
+//!   }
+//!
+//!   sum
+//! }
+//!
+//! assert_eq!(sum_even(&[1, 2, 3, 4]), 6);
+//! ```
+//!
+//! ## Pattern guards
+//!
+//! The most common early-return is “destructure this or bail”. [`guard_let!`] does exactly that:
+//! it binds the variables of a pattern for the rest of the scope, or early-returns the same way
+//! [`guard!`] does if the pattern doesn’t match.
+//!
+//! ```rust
+//! use try_guard::guard_let;
+//!
+//! fn parse_sum(a: &str, b: &str) -> Result<i32, String> {
+//!   guard_let!(Ok(a) = a.parse::<i32>(), format!("{} is not a number", a));
+//!   guard_let!(Ok(b) = b.parse::<i32>(), format!("{} is not a number", b));
+//!   Ok(a + b)
+//! }
+//!
+//! assert_eq!(parse_sum("1", "2"), Ok(3));
+//! assert_eq!(parse_sum("1", "x"), Err("x is not a number".to_owned()));
+//! ```
+//!
+//! ## Reporting failures
+//!
+//! [`GuardFailed`] doesn’t tell you much about what went wrong. [`guard_report!`] early-returns a
+//! [`GuardError`] instead, which captures the failed predicate and where it lives in the code:
+//!
+//! ```rust
+//! use try_guard::{guard_report, GuardError};
+//!
+//! struct User {
+//!   admin: bool
+//! }
+//!
+//! impl User {
+//!   fn is_admin(&self) -> bool {
+//!     self.admin
+//!   }
+//! }
+//!
+//! fn promote(user: &User) -> Result<(), GuardError> {
+//!   guard_report!(user.is_admin());
+//!   Ok(())
+//! }
+//!
+//! let err = promote(&User { admin: false }).unwrap_err();
+//! // guard failed: `user.is_admin()` at src/lib.rs:20:3
+//! println!("{}", err);
+//! ```
+//!
+//! Simple predicates, such as comparisons, are decomposed so that the runtime values of their
+//! operands are captured too:
+//!
+//! ```rust
+//! use try_guard::{guard_report, GuardError};
+//!
+//! fn foo(a: &[u32], limit: usize) -> Result<(), GuardError> {
+//!   guard_report!(a.len() < limit);
+//!   Ok(())
+//! }
+//!
+//! let err = foo(&[1, 2, 3], 2).unwrap_err();
+//! // guard failed: `a.len() < limit` at src/lib.rs:9:3
+//! //   `a.len()` = 3
+//! //   `limit` = 2
+//! println!("{}", err);
+//! ```
+//!
+//! ## Comparisons
+//!
+//! Most guards are comparisons. Akin to the [`assert_eq!`] family, [`guard_eq!`], [`guard_ne!`],
+//! [`guard_lt!`], [`guard_le!`], [`guard_gt!`] and [`guard_ge!`] evaluate both operands exactly
+//! once and early-return with a [`GuardError`] capturing them and the operator — or with the error
+//! passed as third argument:
+//!
+//! ```rust
+//! use try_guard::{guard_eq, guard_le};
+//!
+//! fn resize(buf: &mut Vec<u8>, len: usize, max: usize) -> Result<(), String> {
+//!   guard_le!(len, max, format!("{} is too big", len));
+//!   buf.resize(len, 0);
+//!   guard_eq!(buf.len(), len, "resize failed");
+//!   Ok(())
+//! }
+//! ```
+//!
 //! If you’d rather manipulate the error type when the predicate is false, you might be interested
 //! in the [`verify!`] macro instead. That macro is akin to [`guard!`] but instead doesn’t exit the
 //! current scope: it maps the predicate’s truth to either `Some(())` or `None`, allowing you to
@@ -120,28 +278,757 @@
 //! }
 //! ```
 //!
+//! [`verify!`] can also produce a value, lazily evaluated, with `verify!(cond => value)`. And
+//! because mapping to a [`Result`] is so common, [`verify_or!`] and [`verify_or_else!`] do it for
+//! you:
+//!
+//! ```rust
+//! use try_guard::{verify, verify_or, verify_or_else};
+//!
+//! fn half(x: u32) -> Option<u32> {
+//!   verify!(x % 2 == 0 => x / 2)
+//! }
+//!
+//! fn check_even(x: u32) -> Result<(), String> {
+//!   verify_or!(x % 2 == 0, "odd".to_owned())
+//! }
+//!
+//! fn check_small(x: u32) -> Result<(), String> {
+//!   verify_or_else!(x < 10, || format!("{} is too big", x))
+//! }
+//!
+//! assert_eq!(half(4), Some(2));
+//! assert_eq!(half(3), None);
+//! assert_eq!(check_even(3), Err("odd".to_owned()));
+//! assert_eq!(check_small(12), Err("12 is too big".to_owned()));
+//! ```
+//!
+//! The same checks are available as methods — which are handier in method chains — through the
+//! [`BoolExt`], [`OptionExt`] and [`ResultExt`] extension traits:
+//!
+//! ```rust
+//! use try_guard::{BoolExt, OptionExt, ResultExt};
+//!
+//! fn port(s: &str) -> Option<u16> {
+//!   (!s.is_empty()).guard()?;
+//!   s.parse().ok().guard(|port| *port >= 1024)
+//! }
+//!
+//! fn even(x: Result<u32, String>) -> Result<u32, String> {
+//!   x.ensure(|x| x % 2 == 0, "odd".to_owned())
+//! }
+//!
+//! assert_eq!(port("8080"), Some(8080));
+//! assert_eq!(port("80"), None);
+//! assert_eq!(even(Ok(3)), Err("odd".to_owned()));
+//! ```
+//!
+//! ## Loops and control flow
+//!
+//! In loops and labeled blocks, [`guard_continue!`] and [`guard_break!`] skip to the next
+//! iteration or break out — possibly with a value — instead of returning. And in closures returning
+//! a [`ControlFlow`](std::ops::ControlFlow), such as the ones passed to
+//! [`Iterator::try_for_each`], [`guard!`] breaks with its failure:
+//!
+//! ```rust
+//! use std::ops::ControlFlow;
+//! use try_guard::{guard, guard_break, guard_continue};
+//!
+//! let mut evens = Vec::new();
+//!
+//! for x in 1..10 {
+//!   guard_break!(x < 7);
+//!   guard_continue!(x % 2 == 0);
+//!   evens.push(x);
+//! }
+//!
+//! let flow = evens.iter().try_for_each(|x| {
+//!   guard!(*x < 4, *x);
+//!   ControlFlow::Continue(())
+//! });
+//!
+//! assert_eq!(evens, [2, 4, 6]);
+//! assert_eq!(flow, ControlFlow::Break(4));
+//! ```
+//!
+//! ## Const contexts
+//!
+//! [`guard!`] can’t be used in `const fn`s. [`const_guard!`] can, early-returning `None` — or
+//! `Err(err)` with `const_guard!(cond, err)`. And [`static_guard!`] checks a predicate at compile
+//! time, failing compilation if it’s `false`:
+//!
+//! ```rust
+//! use try_guard::{const_guard, static_guard};
+//!
+//! const SIZES: [usize; 3] = [16, 32, 64];
+//!
+//! const fn size(i: usize) -> Option<usize> {
+//!   const_guard!(i < SIZES.len());
+//!   Some(SIZES[i])
+//! }
+//!
+//! static_guard!(SIZES[0].is_power_of_two());
+//!
+//! assert_eq!(size(1), Some(32));
+//! assert_eq!(size(3), None);
+//! ```
+//!
+//! ## Accumulating failures
+//!
+//! When validating forms or configuration, you typically want to report all the problems at once
+//! rather than only the first one. The [`check!`] macro has the same syntax as [`guard!`] — with a
+//! [`Validator`] as first argument — but records failures in the [`Validator`] instead of
+//! early-returning:
+//!
+//! ```rust
+//! use try_guard::{check, GuardError, Validator};
+//!
+//! fn validate(name: &str, age: u32) -> Result<(), Vec<GuardError>> {
+//!   let mut validator = Validator::new();
+//!   check!(validator, !name.is_empty());
+//!   check!(validator, age >= 18);
+//!   validator.finish()
+//! }
+//!
+//! assert_eq!(validate("", 12).unwrap_err().len(), 2);
+//! ```
+//!
+//! To validate nested structures, a [`ValidationContext`] tracks the [`Path`] of the value being
+//! validated — such as `users[3].address.zip` — and attaches it to failures.
+//!
+//! ## Deriving validation
+//!
+//! Rather than writing validation functions by hand, you can derive the [`Validate`] trait from
+//! `#[guard(…)]` attributes on the fields of a struct, where `self` stands for the field:
+//!
+//! ```rust
+//! use try_guard::Validate;
+//!
+//! #[derive(Validate)]
+//! struct Address {
+//!   #[guard(self.len() == 5)]
+//!   zip: String,
+//! }
+//!
+//! #[derive(Validate)]
+//! struct User {
+//!   #[guard(!self.is_empty())]
+//!   name: String,
+//!   #[guard(range = 18..=130)]
+//!   age: u8,
+//!   #[guard(nested)]
+//!   addresses: Vec<Address>,
+//! }
+//!
+//! let user = User {
+//!   name: String::new(),
+//!   age: 12,
+//!   addresses: vec![Address { zip: "123".to_owned() }],
+//! };
+//!
+//! // stop at the first failure…
+//! assert_eq!(user.validate().unwrap_err().path().to_string(), "name");
+//!
+//! // … or report them all
+//! let errors = user.validate_all().unwrap_err();
+//! assert_eq!(errors.len(), 3);
+//! assert_eq!(errors[2].path().to_string(), "addresses[0].zip");
+//! ```
+//!
+//! ## Refinement types
+//!
+//! Once a value has been guarded, [`Guarded`] carries the proof in its type, so that it never has
+//! to be validated again. It can only be built by checking a [`Predicate`], typically one of the
+//! [`predicates`] module — and [`NonEmpty`], [`Positive`] and [`Bounded`] are shortcuts for the
+//! most common ones:
+//!
+//! ```rust
+//! use try_guard::{Bounded, GuardError, NonEmpty};
+//!
+//! type Percentage = Bounded<u32, 0, 100>;
+//!
+//! fn usage(name: &str, value: u32) -> Result<(NonEmpty<&str>, Percentage), GuardError> {
+//!   Ok((NonEmpty::try_new(name)?, Percentage::try_new(value)?))
+//! }
+//!
+//! let (name, value) = usage("cpu", 42).unwrap();
+//! assert_eq!((name.len(), *value), (3, 42));
+//!
+//! assert!(usage("", 42).is_err());
+//!
+//! // guard failed: `try_guard::predicates::InBounds<0, 100>` at src/lib.rs:6:33
+//! //   expected between 0 and 100
+//! println!("{}", usage("cpu", 142).unwrap_err());
+//! ```
+//!
+//! Predicates can be combined with [`Predicate::and`], [`Predicate::or`], [`Predicate::not`] and
+//! [`Predicate::map`], and checked directly with `guard!(value => predicate)`. With
+//! [`guard_report!`] — or [`Predicate::verify`], which doesn’t early-return — the [`GuardError`]
+//! then tells which part of the predicate failed.
+//!
+//! ## Contracts
+//!
+//! Guards at the top of a function are really preconditions. The [`requires`] attribute makes
+//! them part of the signature — and of the documentation — of the function. Its [`ensures`]
+//! counterpart checks postconditions, which can refer to the returned value as `ret` and to values
+//! on entry as `old(expr)`:
+//!
+//! ```rust
+//! use try_guard::{ensures, requires};
+//!
+//! /// Split `slice` in chunks of `size` elements.
+//! #[requires(size > 0)]
+//! #[requires(slice.len() % size == 0)]
+//! #[ensures(ret.len() == slice.len() / size)]
+//! fn chunks(slice: &[u8], size: usize) -> Option<Vec<&[u8]>> {
+//!   Some(slice.chunks(size).collect())
+//! }
+//!
+//! assert_eq!(chunks(&[1, 2, 3, 4], 2), Some(vec![&[1, 2][..], &[3, 4][..]]));
+//! assert_eq!(chunks(&[1, 2, 3], 2), None);
+//! assert_eq!(chunks(&[1, 2, 3], 0), None);
+//! ```
+//!
+//! Invariants of a type can be declared with the [`invariant`] attribute on an `impl` block: they
+//! are checked at the end of each of its `&mut self` methods. And contracts can be attached to the
+//! methods of a trait with [`contract`], which generates checked versions of them for all
+//! implementors.
+//!
+//! Conversely, the [`guarded`] attribute documents the guards found in the body of a function, as
+//! an *Errors* or *Preconditions* section, so that the documentation can’t drift from the code.
+//!
 //! ## Feature flags
 //!
-//!   - The `test-nightly` feature flag can be used to test nightly-related features that come
-//!     freely and don’t require a nightly build of rustc to compile this crate but require one at
-//!     use site.
+//!   - The `nightly` feature flag adds [`guard_try!`], which early-exits through the [`?`]
+//!     operator and [`GuardResidual`], based on the `try_trait_v2` feature. It requires a nightly
+//!     build of rustc, and doesn’t change the other macros.
+//!   - The `serde` feature flag implements `Serialize` and `Deserialize` for [`Guarded`], checking
+//!     its predicate while deserializing, and adds `deserialize_with` helpers in the `serde`
+//!     module.
 //!
+//! [`check!`]: check
+//! [`const_guard!`]: const_guard
 //! [`guard!`]: guard
+//! [`guard_break!`]: guard_break
+//! [`guard_continue!`]: guard_continue
+//! [`guard_eq!`]: guard_eq
+//! [`guard_ge!`]: guard_ge
+//! [`guard_gt!`]: guard_gt
+//! [`guard_le!`]: guard_le
+//! [`guard_let!`]: guard_let
+//! [`guard_lt!`]: guard_lt
+//! [`guard_ne!`]: guard_ne
+//! [`guard_report!`]: guard_report
+//! [`guard_try!`]: https://docs.rs/try-guard/latest/try_guard/macro.guard_try.html
+//! [`static_guard!`]: static_guard
 //! [`verify!`]: verify
+//! [`verify_or!`]: verify_or
+//! [`verify_or_else!`]: verify_or_else
+//! [`Validate`]: trait@Validate
+//! [`predicates`]: mod@predicates
+//! [`contract`]: macro@contract
+//! [`ensures`]: macro@ensures
+//! [`guarded`]: macro@guarded
+//! [`invariant`]: macro@invariant
+//! [`requires`]: macro@requires
 //! [`guard`]: http://hackage.haskell.org/package/base-4.12.0.0/docs/Control-Monad.html#v:guard
 //! [`?`]: https://doc.rust-lang.org/std/ops/trait.Try.html
-//! [`Try<Error = NoneError>`]: https://doc.rust-lang.org/std/ops/trait.Try.html
+//! [`GuardResidual`]: https://docs.rs/try-guard/latest/try_guard/struct.GuardResidual.html
+//! [`FromResidual<GuardResidual>`]: https://doc.rust-lang.org/std/ops/trait.FromResidual.html
+
+#![cfg_attr(feature = "nightly", feature(try_trait_v2, try_trait_v2_residual))]
+
+mod cmp;
+mod const_guard;
+mod contract;
+mod control_flow;
+mod empty;
+mod error;
+mod ext;
+#[cfg(feature = "nightly")]
+mod nightly;
+mod path;
+pub mod predicates;
+mod refine;
+mod report;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod validate;
+
+pub use crate::contract::set_invariant_log_hook;
+pub use crate::empty::GuardEmpty;
+pub use crate::error::{GuardError, GuardFailed, Operand};
+pub use crate::ext::{BoolExt, OptionExt, ResultExt};
+#[cfg(feature = "nightly")]
+pub use crate::nightly::GuardResidual;
+pub use crate::path::{Path, PathSegment, PrefixPath};
+pub use crate::predicates::Predicate;
+pub use crate::refine::{Bounded, Guarded, NonEmpty, Positive};
+pub use crate::validate::{Validate, ValidationContext, Validator};
+/// Derive [`Validate`](trait@Validate) from `#[guard(…)]` field attributes.
+///
+/// Each field can have any number of `#[guard(…)]` attributes, checked in order:
+///
+///   - `#[guard(pred)]` checks the predicate `pred`, in which `self` stands for the field. Failures
+///     are reported as with [`guard_report!`].
+///   - `#[guard(range = range)]` checks that the field is contained in `range`.
+///   - `#[guard(nested)]` validates the field, which must implement
+///     [`Validate`](trait@Validate) too. The type parameters appearing in its type get a
+///     [`Validate`](trait@Validate) bound.
+///
+/// Failures get the name of the field — or its index for tuple structs — prepended to their
+/// [`Path`].
+///
+/// ```rust
+/// use try_guard::Validate;
+///
+/// #[derive(Validate)]
+/// struct Port(#[guard(self >= 1024)] u16);
+///
+/// #[derive(Validate)]
+/// struct Server {
+///   #[guard(self.contains(':'))]
+///   #[guard(!self.starts_with(':'))]
+///   host: String,
+///   #[guard(nested)]
+///   port: Port,
+/// }
+///
+/// let server = Server { host: "localhost:".to_owned(), port: Port(80) };
+/// let err = server.validate().unwrap_err();
+///
+/// assert_eq!(err.path().to_string(), "port.0");
+/// assert_eq!(err.expr(), "self.0 >= 1024");
+/// ```
+///
+/// Only structs are supported.
+///
+/// [`guard_report!`]: crate::guard_report
+pub use try_guard_derive::Validate;
+/// Precondition of a function.
+///
+/// `#[requires(…)]` takes the same arguments as [`guard!`] and checks them on entry of the
+/// function it annotates: `#[requires(cond)]` early-returns like `guard!(cond)`,
+/// `#[requires(cond, err)]` like `guard!(cond, err)`, etc. The return type of the function thus
+/// decides how it fails. Several preconditions can be stacked; they’re checked in order.
+///
+/// Preconditions are listed in a *Preconditions* section appended to the documentation of the
+/// function, so that callers see the contract without reading its body.
+///
+/// ```rust
+/// use try_guard::requires;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Error {
+///   Empty,
+///   TooLong,
+/// }
+///
+/// #[requires(!name.is_empty(), Error::Empty)]
+/// #[requires(name.len() <= 16, Error::TooLong)]
+/// fn greet(name: &str) -> Result<String, Error> {
+///   Ok(format!("Hello, {}!", name))
+/// }
+///
+/// #[requires(b != 0)]
+/// fn div(a: i32, b: i32) -> Option<i32> {
+///   Some(a / b)
+/// }
+///
+/// assert_eq!(greet(""), Err(Error::Empty));
+/// assert_eq!(greet("Alice"), Ok("Hello, Alice!".to_owned()));
+/// assert_eq!(div(1, 0), None);
+/// ```
+///
+/// [`guard!`]: crate::guard
+pub use try_guard_derive::requires;
+/// Postcondition of a function.
+///
+/// `#[ensures(…)]` takes the same arguments as [`guard!`] and checks them before the function it
+/// annotates returns — even if it returns early, with `return` or a guard. The condition can refer
+/// to:
+///
+///   - `ret`, a reference to the returned value. For functions returning an [`Option`] or a
+///     [`Result`], it’s the wrapped value, and postconditions are only checked if the function
+///     succeeded.
+///   - `old(expr)`, a clone of `expr` as evaluated on entry of the function.
+///
+/// A violated postcondition fails as [`guard!`] would. Postconditions are listed in a
+/// *Postconditions* section appended to the documentation of the function, after the
+/// preconditions from [`requires`](macro@requires), if any.
+///
+/// The body of the function runs in a closure, so that returning early from it still checks the
+/// postconditions. For functions taking a `&mut` reference and returning a borrow — e.g.
+/// `fn last_mut(&mut self) -> Option<&mut u8>` — the closure takes the reference, so that
+/// postconditions can only refer to `ret` and to the other arguments.
+///
+/// ```rust
+/// use try_guard::ensures;
+///
+/// struct Stack {
+///   items: Vec<u32>,
+/// }
+///
+/// impl Stack {
+///   #[ensures(self.items.len() == old(self.items.len()) + 1)]
+///   #[ensures(self.items.last() == Some(&item))]
+///   fn push(&mut self, item: u32) -> Option<()> {
+///     self.items.push(item);
+///     Some(())
+///   }
+///
+///   #[ensures(self.items.len() == old(self.items.len()) - 1)]
+///   fn pop(&mut self) -> Option<u32> {
+///     self.items.pop()
+///   }
+/// }
+///
+/// #[ensures(ret.len() <= input.len())]
+/// fn trim(input: &str) -> Option<&str> {
+///   Some(input.trim())
+/// }
+///
+/// let mut stack = Stack { items: Vec::new() };
+/// assert_eq!(stack.push(3), Some(()));
+/// assert_eq!(stack.pop(), Some(3));
+/// assert_eq!(stack.pop(), None);
+/// assert_eq!(trim("  foo "), Some("foo"));
+/// ```
+///
+/// Async functions are not supported:
+///
+/// ```compile_fail
+/// use try_guard::ensures;
+///
+/// #[ensures(*ret > 0)]
+/// async fn answer() -> Option<u32> {
+///   Some(42)
+/// }
+/// ```
+///
+/// [`guard!`]: crate::guard
+pub use try_guard_derive::ensures;
+/// Invariant of a type.
+///
+/// `#[invariant(cond)]` annotates an `impl` block. `cond`, which refers to the value as `self`,
+/// is checked at the end of every method of the block taking `&mut self` — even if it returns
+/// early. Several invariants can be stacked; they’re checked in order, and listed in an
+/// *Invariants* section appended to the documentation of the block.
+///
+/// A violated invariant is reported as a [`GuardError`], as with [`guard_report!`]:
+///
+///   - Methods returning an [`Option`] or a [`Result`] early-return with it, as [`guard_report!`]
+///     would. The error type of a [`Result`] must thus implement [`From<GuardError>`].
+///   - Other methods panic. With `#[invariant(cond, policy = log)]`, they report the error to the
+///     hook set with [`set_invariant_log_hook`] — which prints it on the standard error by
+///     default — instead; `#[invariant(cond, policy = panic)]` is the default.
+///
+/// Methods returning a borrow — e.g. `fn last_mut(&mut self) -> Option<&mut u8>` — are not
+/// checked, since `self` can’t be used while the borrow is alive, and neither are `async` methods.
+/// Other methods can opt out with `#[invariant(skip)]`; that’s needed for methods returning types
+/// with elided lifetimes, such as `std::slice::IterMut<u8>`.
+///
+/// ```rust
+/// use try_guard::{invariant, GuardError};
+///
+/// struct Account {
+///   balance: i64,
+/// }
+///
+/// #[invariant(self.balance >= 0)]
+/// impl Account {
+///   fn deposit(&mut self, amount: i64) {
+///     self.balance += amount;
+///   }
+///
+///   fn withdraw(&mut self, amount: i64) -> Result<(), GuardError> {
+///     self.balance -= amount;
+///     Ok(())
+///   }
+///
+///   #[invariant(skip)]
+///   fn reset(&mut self) {
+///     self.balance = -1;
+///   }
+/// }
+///
+/// let mut account = Account { balance: 0 };
+/// account.deposit(10);
+///
+/// let err = account.withdraw(20).unwrap_err();
+/// assert_eq!(err.expr(), "self.balance >= 0");
+/// assert_eq!(err.operands()[0].value(), Some("-10"));
+///
+/// // not checked
+/// account.reset();
+/// ```
+///
+/// [`guard_report!`]: crate::guard_report
+pub use try_guard_derive::invariant;
+/// Contracts of a trait.
+///
+/// `#[contract]` annotates a trait whose method declarations have [`requires`](macro@requires)
+/// and [`ensures`](macro@ensures) attributes. For a trait `Foo`, it generates a `FooChecked`
+/// extension trait, implemented for every implementor of `Foo`, with a `checked_method` method for
+/// each `method` with a contract. `checked_method` checks the contract around the implementation
+/// of `method`, failing as [`guard!`] would.
+///
+/// The contracts are also listed in the documentation of the methods.
+///
+/// ```rust
+/// use try_guard::contract;
+///
+/// #[contract]
+/// trait Shape {
+///   fn area(&self) -> f64;
+///
+///   #[requires(factor > 0.)]
+///   #[ensures(self.area() >= old(self.area()))]
+///   fn grow(&mut self, factor: f64) -> Option<()>;
+/// }
+///
+/// struct Square(f64);
+///
+/// impl Shape for Square {
+///   fn area(&self) -> f64 {
+///     self.0 * self.0
+///   }
+///
+///   fn grow(&mut self, factor: f64) -> Option<()> {
+///     self.0 *= factor;
+///     Some(())
+///   }
+/// }
+///
+/// let mut square = Square(2.);
+/// assert_eq!(square.checked_grow(2.), Some(()));
+/// assert_eq!(square.checked_grow(-1.), None);
+/// assert_eq!(square.checked_grow(0.5), None);
+/// ```
+///
+/// [`guard!`]: crate::guard
+pub use try_guard_derive::contract;
+/// Document the guards of a function.
+///
+/// `#[guarded]` scans the body of the function it annotates for [`guard!`], [`guard_let!`],
+/// [`guard_report!`] and comparison guards such as [`guard_eq!`], and appends a section listing
+/// them to its documentation — so that it can’t drift from the code:
+///
+///   - Functions returning a [`Result`] get an *Errors* section, listing each condition along with
+///     the error returned if it doesn’t hold.
+///   - Other functions get a *Preconditions* section, listing each condition.
+///
+/// ```rust
+/// use try_guard::{guard, guard_eq, guarded, GuardError};
+///
+/// #[derive(Debug)]
+/// enum Error {
+///   Empty,
+///   Guard(GuardError),
+/// }
+///
+/// impl From<GuardError> for Error {
+///   fn from(err: GuardError) -> Self {
+///     Error::Guard(err)
+///   }
+/// }
+///
+/// /// Parse a list of pairs.
+/// ///
+/// /// The documentation of this function ends with:
+/// ///
+/// /// > # Errors
+/// /// >
+/// /// >   - `Error::Empty` unless `!input.is_empty()`.
+/// /// >   - `GuardError` unless `list.len() % 2 == 0`.
+/// #[guarded]
+/// fn pairs(input: &str) -> Result<Vec<&str>, Error> {
+///   guard!(!input.is_empty(), Error::Empty);
+///   let list = input.split(',').collect::<Vec<_>>();
+///   guard_eq!(list.len() % 2, 0);
+///   Ok(list)
+/// }
+///
+/// assert!(pairs("a,b").is_ok());
+/// ```
+///
+/// Only the guards of the function are documented, not the ones of the functions it calls, nor the
+/// ones of the closures and functions defined in its body.
+///
+/// [`guard!`]: crate::guard
+/// [`guard_eq!`]: crate::guard_eq
+/// [`guard_let!`]: crate::guard_let
+/// [`guard_report!`]: crate::guard_report
+pub use try_guard_derive::guarded;
 
 /// The [`guard!`] macro.
 ///
+/// If the predicate is `false`, the current function early-returns with
+/// [`GuardTarget::guard_failed`]. By default, the failure is [`GuardFailed`]; you can pass your own
+/// error as second argument, in which case it’s only evaluated if the predicate is `false`.
+///
+/// `guard!(value => predicate)` checks that `value` satisfies a [`Predicate`]. Use
+/// [`guard_report!`] instead to know which part of the predicate failed:
+///
+/// ```rust
+/// use try_guard::guard;
+/// use try_guard::predicates::{IsNonEmpty, IsPositive};
+/// use try_guard::Predicate;
+///
+/// fn average(xs: Vec<f32>) -> Option<f32> {
+///   guard!(xs => IsNonEmpty);
+///   guard!(xs => |xs: &Vec<f32>| xs.iter().all(|x| IsPositive.test(x)));
+///   Some(xs.iter().sum::<f32>() / xs.len() as f32)
+/// }
+///
+/// assert_eq!(average(vec![1., 2.]), Some(1.5));
+/// assert_eq!(average(vec![1., -2.]), None);
+/// assert_eq!(average(Vec::new()), None);
+/// ```
+///
+/// You can also provide an `else` block, run if the predicate is `false`, with
+/// `guard!(cond else { … })`. The block must diverge:
+///
+/// ```compile_fail
+/// use try_guard::guard;
+///
+/// fn foo(cond: bool) -> i32 {
+///   guard!(cond else { println!("falling through"); });
+///   42
+/// }
+/// ```
+///
 /// [`guard!`]: guard
+/// [`guard_report!`]: guard_report
 #[macro_export]
 macro_rules! guard {
-  ($e:expr) => {
+  (@else [$($cond:tt)+] else $b:block) => {
+    if !($($cond)+) {
+      #[allow(clippy::diverging_sub_expression)]
+      let _: $crate::__private::Infallible = $b;
+    }
+  };
+
+  (@else [$($cond:tt)*] $t:tt $($rest:tt)*) => {
+    $crate::guard!(@else [$($cond)* $t] $($rest)*)
+  };
+
+  ($v:expr => $p:expr $(,)?) => {
+    if !$crate::Predicate::test(&$p, &$v) {
+      $crate::__guard_fail!($crate::GuardFailed)
+    }
+  };
+
+  ($v:expr => $p:expr, $err:expr $(,)?) => {
+    if !$crate::Predicate::test(&$p, &$v) {
+      $crate::__guard_fail!($err)
+    }
+  };
+
+  ($e:expr $(,)?) => {
     if !$e {
-      None?
+      $crate::__guard_fail!($crate::GuardFailed)
     }
   };
+
+  ($e:expr, $err:expr $(,)?) => {
+    if !$e {
+      $crate::__guard_fail!($err)
+    }
+  };
+
+  ($($t:tt)+) => {
+    $crate::guard!(@else [] $($t)+)
+  };
+}
+
+/// Pattern-binding version of [`guard!`].
+///
+/// `guard_let!(pat = expr)` binds the variables of `pat` for the rest of the enclosing scope if
+/// `expr` matches it, and early-returns with [`GuardTarget::guard_failed`] otherwise. As with
+/// [`guard!`], an error can be passed as second argument: `guard_let!(pat = expr, err)`.
+///
+/// This expands to a `let … else` statement, so it requires a compiler that supports it (Rust
+/// 1.65+), whatever the edition.
+///
+/// ```rust
+/// use try_guard::guard_let;
+///
+/// fn first_even(xs: &[u32]) -> Option<u32> {
+///   guard_let!(Some(x) = xs.iter().find(|x| *x % 2 == 0));
+///   Some(*x)
+/// }
+///
+/// assert_eq!(first_even(&[1, 2, 3]), Some(2));
+/// assert_eq!(first_even(&[1, 3]), None);
+/// ```
+///
+/// [`guard!`]: guard
+#[macro_export]
+macro_rules! guard_let {
+  ($p:pat = $e:expr $(,)?) => {
+    let $p = $e else {
+      $crate::__guard_fail!($crate::GuardFailed)
+    };
+  };
+
+  ($p:pat = $e:expr, $err:expr $(,)?) => {
+    let $p = $e else {
+      $crate::__guard_fail!($err)
+    };
+  };
+}
+
+/// Early-return with a failure, through [`GuardTarget`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guard_fail {
+  ($f:expr) => {
+    return $crate::GuardTarget::guard_failed($f)
+  };
+}
+
+#[doc(hidden)]
+pub mod __private {
+  pub use std::convert::Infallible;
+
+  pub use crate::contract::{
+    call_once, invariant_log, invariant_panic, Output, Succeeded, SucceededFallback,
+  };
+  pub use crate::report::{Capture, CaptureDebug, CaptureFallback};
+
+  #[cfg(feature = "nightly")]
+  pub use crate::nightly::Guard;
+}
+
+/// Types [`guard!`] can early-return with.
+///
+/// `F` is the failure reported by the guard — [`GuardFailed`] by default, or the user-provided
+/// error. This is implemented for [`Option<T>`], which gives [`None`] and drops the failure, for
+/// [`Result<T, E>`] when `E: From<F>`, which gives an [`Err`], for
+/// [`ControlFlow<B, C>`](std::ops::ControlFlow) when `B: From<F>` — or when `B` is `()` and `F` is
+/// [`GuardFailed`] — which gives a [`Break`](std::ops::ControlFlow::Break), and for [`GuardEmpty`]
+/// types, which give their empty value.
+///
+/// [`guard!`]: guard
+pub trait GuardTarget<F = GuardFailed> {
+  /// Value to return when a guard fails with `failure`.
+  fn guard_failed(failure: F) -> Self;
+}
+
+impl<T, F> GuardTarget<F> for Option<T> {
+  fn guard_failed(_: F) -> Self {
+    None
+  }
+}
+
+impl<T, E, F> GuardTarget<F> for Result<T, E>
+where
+  E: From<F>,
+{
+  fn guard_failed(failure: F) -> Self {
+    Err(failure.into())
+  }
 }
 
 /// A version of [`guard!`] that doesn’t shortcut.
@@ -149,14 +1036,62 @@ macro_rules! guard {
 /// The advantage of this macro over [`guard!`] is to allow you to manipulate the resulting
 /// [`Option`].
 ///
+/// `verify!(cond)` gives `Some(())` if `cond` is `true`. `verify!(cond => value)` gives
+/// `Some(value)` instead, `value` being evaluated only if `cond` is `true`. These are the same as
+/// [`BoolExt::guard`] and [`BoolExt::guard_then`]. To check a [`Predicate`] and know why it failed,
+/// use [`Predicate::verify`].
+///
 /// [`guard!`]: guard
 #[macro_export]
 macro_rules! verify {
+  ($e:expr => $v:expr) => {
+    $crate::verify_then!($e, $v)
+  };
+
   ($e:expr) => {
-    if !$e {
-      None
-    } else {
-      Some(())
+    $crate::BoolExt::guard($e)
+  };
+}
+
+/// Alias of `verify!(cond => value)`.
+///
+/// `verify_then!(cond, value)` gives `Some(value)` if `cond` is `true`, `value` being evaluated
+/// only in that case.
+#[macro_export]
+macro_rules! verify_then {
+  ($e:expr, $v:expr $(,)?) => {
+    // not guard_then, so that $v can early-return
+    match $crate::BoolExt::guard($e) {
+      ::core::option::Option::Some(()) => ::core::option::Option::Some($v),
+      ::core::option::Option::None => ::core::option::Option::None,
     }
-  }
+  };
+}
+
+/// A version of [`verify!`] giving a [`Result`].
+///
+/// `verify_or!(cond, err)` gives `Ok(())` if `cond` is `true` and `Err(err)` otherwise. As with
+/// [`Option::ok_or`], `err` is eagerly evaluated; see [`verify_or_else!`] for a lazy version. This is
+/// the same as [`BoolExt::guard_or`].
+///
+/// [`verify!`]: verify
+/// [`verify_or_else!`]: verify_or_else
+#[macro_export]
+macro_rules! verify_or {
+  ($e:expr, $err:expr $(,)?) => {
+    $crate::BoolExt::guard_or($e, $err)
+  };
+}
+
+/// A version of [`verify_or!`] constructing the error lazily.
+///
+/// `verify_or_else!(cond, f)` gives `Ok(())` if `cond` is `true` and `Err(f())` otherwise. This is
+/// the same as [`BoolExt::guard_or_else`].
+///
+/// [`verify_or!`]: verify_or
+#[macro_export]
+macro_rules! verify_or_else {
+  ($e:expr, $f:expr $(,)?) => {
+    $crate::BoolExt::guard_or_else($e, $f)
+  };
 }
@@ -0,0 +1,114 @@
+//! Nightly integration with the [`Try`] and [`FromResidual`] traits.
+
+use std::convert::Infallible;
+use std::ops::{ControlFlow, FromResidual, Residual, Try};
+
+use crate::GuardFailed;
+
+/// Version of [`guard!`] early-exiting through the `?` operator.
+///
+/// `guard_try!(cond)` and `guard_try!(cond, err)` apply `?` to a [`GuardResidual`] if `cond` is
+/// `false`, so that they work in `try` blocks and in functions returning any type implementing
+/// [`FromResidual<GuardResidual<F>>`](FromResidual), `F` being the failure — [`GuardFailed`] by
+/// default, or `err`.
+///
+/// ```rust
+/// #![feature(try_blocks_heterogeneous)]
+///
+/// use try_guard::guard_try;
+///
+/// let x = 3;
+/// let half = try bikeshed Option<i32> {
+///   guard_try!(x % 2 == 0);
+///   x / 2
+/// };
+///
+/// assert_eq!(half, None);
+/// ```
+///
+/// [`guard!`]: crate::guard
+#[macro_export]
+macro_rules! guard_try {
+  ($e:expr $(,)?) => {
+    $crate::guard_try!($e, $crate::GuardFailed)
+  };
+
+  ($e:expr, $err:expr $(,)?) => {
+    if !$e {
+      match $crate::__private::Guard::<_, $crate::__private::Infallible>::Failed($err)? {}
+    }
+  };
+}
+
+/// Residual of a failed guard.
+///
+/// [`guard_try!`] early-exits through the `?` operator with a `GuardResidual<F>`, where `F` is the
+/// failure — [`GuardFailed`] by default, or the user-provided error.
+///
+/// [`guard_try!`]: crate::guard_try
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GuardResidual<F = GuardFailed>(pub F);
+
+impl<T, F> FromResidual<GuardResidual<F>> for Option<T> {
+  fn from_residual(_: GuardResidual<F>) -> Self {
+    None
+  }
+}
+
+impl<T, E, F> FromResidual<GuardResidual<F>> for Result<T, E>
+where
+  E: From<F>,
+{
+  fn from_residual(residual: GuardResidual<F>) -> Self {
+    Err(residual.0.into())
+  }
+}
+
+impl<B, C, F> FromResidual<GuardResidual<F>> for ControlFlow<B, C>
+where
+  B: From<F>,
+{
+  fn from_residual(residual: GuardResidual<F>) -> Self {
+    ControlFlow::Break(residual.0.into())
+  }
+}
+
+impl<C> FromResidual<GuardResidual<GuardFailed>> for ControlFlow<(), C> {
+  fn from_residual(_: GuardResidual<GuardFailed>) -> Self {
+    ControlFlow::Break(())
+  }
+}
+
+impl<F, O> Residual<O> for GuardResidual<F> {
+  type TryType = Guard<F, O>;
+}
+
+/// [`Try`] type the guard macros apply `?` to.
+#[derive(Debug)]
+pub enum Guard<F, O = Infallible> {
+  Passed(O),
+  Failed(F),
+}
+
+impl<F, O> Try for Guard<F, O> {
+  type Output = O;
+
+  type Residual = GuardResidual<F>;
+
+  fn from_output(output: Self::Output) -> Self {
+    Guard::Passed(output)
+  }
+
+  fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+    match self {
+      Guard::Passed(output) => ControlFlow::Continue(output),
+      Guard::Failed(failure) => ControlFlow::Break(GuardResidual(failure)),
+    }
+  }
+}
+
+impl<F, O> FromResidual<GuardResidual<F>> for Guard<F, O> {
+  fn from_residual(residual: GuardResidual<F>) -> Self {
+    Guard::Failed(residual.0)
+  }
+}
@@ -0,0 +1,136 @@
+//! Paths to validated values.
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// Segment of a [`Path`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PathSegment {
+  /// Named field — or key — of a structure.
+  Field(Cow<'static, str>),
+  /// Index of an element in a sequence.
+  Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      PathSegment::Field(ref name) => f.write_str(name),
+      PathSegment::Index(index) => write!(f, "[{}]", index),
+    }
+  }
+}
+
+/// Location of a value in a nested structure, such as `users[3].address.zip`.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Path {
+  segments: Vec<PathSegment>,
+}
+
+impl Path {
+  /// Empty path, locating the root value.
+  pub fn new() -> Self {
+    Path::default()
+  }
+
+  /// Segments of the path.
+  pub fn segments(&self) -> &[PathSegment] {
+    &self.segments
+  }
+
+  /// Whether the path locates the root value.
+  pub fn is_empty(&self) -> bool {
+    self.segments.is_empty()
+  }
+
+  /// Append a segment.
+  pub fn push(&mut self, segment: PathSegment) {
+    self.segments.push(segment);
+  }
+
+  /// Remove the last segment, if any.
+  pub fn pop(&mut self) -> Option<PathSegment> {
+    self.segments.pop()
+  }
+
+  /// [JSON pointer](https://tools.ietf.org/html/rfc6901) representation of the path, such as
+  /// `/users/3/address/zip`.
+  pub fn to_json_pointer(&self) -> String {
+    let mut pointer = String::new();
+
+    for segment in &self.segments {
+      pointer.push('/');
+
+      match *segment {
+        PathSegment::Field(ref name) => {
+          pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+        }
+        PathSegment::Index(index) => pointer.push_str(&index.to_string()),
+      }
+    }
+
+    pointer
+  }
+}
+
+impl fmt::Display for Path {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, segment) in self.segments.iter().enumerate() {
+      if i > 0 {
+        if let PathSegment::Field(_) = *segment {
+          f.write_str(".")?;
+        }
+      }
+
+      segment.fmt(f)?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Values that can be located with a [`Path`].
+///
+/// This is used by [`ValidationContext`] to attach the path of the value being validated to
+/// failures.
+///
+/// [`ValidationContext`]: crate::ValidationContext
+pub trait PrefixPath {
+  /// Prepend `prefix` to the path of `self`.
+  fn prefix_path(self, prefix: &[PathSegment]) -> Self;
+}
+
+impl PrefixPath for () {
+  fn prefix_path(self, _: &[PathSegment]) -> Self {}
+}
+
+impl<T> PrefixPath for Option<T> {
+  fn prefix_path(self, _: &[PathSegment]) -> Self {
+    self
+  }
+}
+
+impl<T, E> PrefixPath for Result<T, E>
+where
+  E: PrefixPath,
+{
+  fn prefix_path(self, prefix: &[PathSegment]) -> Self {
+    self.map_err(|e| e.prefix_path(prefix))
+  }
+}
+
+impl<E> PrefixPath for Vec<E>
+where
+  E: PrefixPath,
+{
+  fn prefix_path(self, prefix: &[PathSegment]) -> Self {
+    self.into_iter().map(|e| e.prefix_path(prefix)).collect()
+  }
+}
+
+impl PrefixPath for Path {
+  fn prefix_path(mut self, prefix: &[PathSegment]) -> Self {
+    self.segments.splice(0..0, prefix.iter().cloned());
+    self
+  }
+}
@@ -0,0 +1,701 @@
+//! Predicates and their combinators.
+//!
+//! A [`Predicate`] can be checked by [`guard!`] with `guard!(value => predicate)`, and by
+//! [`guard_report!`] and [`check!`], which then report which part of the predicate failed:
+//!
+//! ```rust
+//! use try_guard::predicates::{InBounds, IsNonEmpty};
+//! use try_guard::{guard_report, GuardError, Predicate};
+//!
+//! fn register(name: String) -> Result<String, GuardError> {
+//!   let valid = IsNonEmpty.and(InBounds::<3, 16>.map(|name: &String| name.len()));
+//!   guard_report!(name => valid);
+//!   Ok(name)
+//! }
+//!
+//! assert!(register("alice".to_owned()).is_ok());
+//! assert_eq!(register(String::new()).unwrap_err().reason(), Some("non-empty"));
+//! assert_eq!(register("al".to_owned()).unwrap_err().reason(), Some("between 3 and 16"));
+//! ```
+//!
+//! Closures taking a reference to the value and returning a [`bool`] are predicates too.
+//!
+//! [`Predicate::verify`] checks a predicate without early-returning, the same way [`verify!`] checks
+//! a condition, but gives a descriptive [`GuardError`] on failure.
+//!
+//! # Ready-made predicates
+//!
+//! Common predicates are built by the functions of this module:
+//!
+//!   - Ranges: [`in_range`], [`between`] — and [`InBounds`], whose bounds are part of its type.
+//!   - Collections: [`non_empty`], [`len_between`], [`all_unique`].
+//!   - Strings: [`is_ascii`], [`max_bytes`], [`no_control_chars`], [`trimmed`].
+//!   - Numbers: [`finite`], [`positive`], [`multiple_of`].
+//!
+//! Each of them describes itself, so that failures are reported with a descriptive
+//! [`GuardError::reason`]:
+//!
+//! ```rust
+//! use try_guard::predicates::{between, max_bytes, multiple_of, no_control_chars, trimmed};
+//! use try_guard::{check, verify, GuardError, Predicate, Validator};
+//!
+//! fn validate(name: &str, port: u16) -> Result<(), Vec<GuardError>> {
+//!   let mut validator = Validator::new();
+//!   check!(validator, name => trimmed().and(no_control_chars()).and(max_bytes(16)));
+//!   check!(validator, port => between(1024, 49151));
+//!   validator.finish()
+//! }
+//!
+//! let errors = validate(" app\n", 80).unwrap_err();
+//! assert_eq!(errors[0].reason(), Some("without leading or trailing whitespace"));
+//! assert_eq!(errors[1].reason(), Some("between 1024 and 49151"));
+//!
+//! assert_eq!(verify!(multiple_of(4).test(&12) => "aligned"), Some("aligned"));
+//! ```
+//!
+//! [`check!`]: crate::check
+//! [`GuardError::reason`]: crate::GuardError::reason
+//! [`guard!`]: crate::guard
+//! [`guard_report!`]: crate::guard_report
+//! [`verify!`]: crate::verify
+
+use std::any::type_name;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::convert::TryInto;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+
+use crate::GuardError;
+
+/// Predicate on values of type `T`.
+pub trait Predicate<T: ?Sized> {
+  /// Whether `value` satisfies the predicate.
+  fn test(&self, value: &T) -> bool;
+
+  /// Description of what the predicate requires, such as `non-empty`.
+  fn describe(&self) -> String;
+
+  /// Description of the part of the predicate `value` doesn’t satisfy, if any.
+  fn explain(&self, value: &T) -> Option<String> {
+    if self.test(value) {
+      None
+    } else {
+      Some(self.describe())
+    }
+  }
+
+  /// Check that `value` satisfies the predicate, without early-returning.
+  ///
+  /// On failure, the [`GuardError`] gives the description of the failed part of the predicate as
+  /// [`GuardError::reason`], and the location of the caller — but not its module path.
+  ///
+  /// ```rust
+  /// use try_guard::predicates::between;
+  /// use try_guard::Predicate;
+  ///
+  /// assert!(between(1, 10).verify(&5).is_ok());
+  ///
+  /// let err = between(1, 10).verify(&12).unwrap_err();
+  /// assert_eq!(err.reason(), Some("between 1 and 10"));
+  /// ```
+  #[track_caller]
+  fn verify(&self, value: &T) -> Result<(), GuardError> {
+    let reason = match self.explain(value) {
+      Some(reason) => reason,
+      None => return Ok(()),
+    };
+
+    Err(GuardError::at_caller(type_name::<Self>()).with_reason(reason))
+  }
+
+  /// Predicate satisfied if both `self` and `rhs` are.
+  fn and<Q>(self, rhs: Q) -> And<Self, Q, T>
+  where
+    Self: Sized,
+    Q: Predicate<T>,
+  {
+    And {
+      lhs: self,
+      rhs,
+      value: PhantomData,
+    }
+  }
+
+  /// Predicate satisfied if either `self` or `rhs` is.
+  fn or<Q>(self, rhs: Q) -> Or<Self, Q, T>
+  where
+    Self: Sized,
+    Q: Predicate<T>,
+  {
+    Or {
+      lhs: self,
+      rhs,
+      value: PhantomData,
+    }
+  }
+
+  /// Predicate satisfied if `self` isn’t.
+  fn not(self) -> Not<Self, T>
+  where
+    Self: Sized,
+  {
+    Not {
+      predicate: self,
+      value: PhantomData,
+    }
+  }
+
+  /// Predicate on values of type `U`, satisfied if `f(value)` satisfies `self`.
+  fn map<U, F>(self, f: F) -> Map<Self, F, T>
+  where
+    Self: Sized,
+    T: Sized,
+    U: ?Sized,
+    F: Fn(&U) -> T,
+  {
+    Map {
+      predicate: self,
+      f,
+      value: PhantomData,
+    }
+  }
+}
+
+impl<T, F> Predicate<T> for F
+where
+  T: ?Sized,
+  F: Fn(&T) -> bool,
+{
+  fn test(&self, value: &T) -> bool {
+    self(value)
+  }
+
+  fn describe(&self) -> String {
+    "custom predicate".to_owned()
+  }
+}
+
+// the combinators are parameterized by the type of the values they test so that it can be inferred
+// from the values instead of the sub-predicates, which often are predicates on several types
+macro_rules! impl_combinator {
+  ($($name:ident<$($p:ident),+> { $($field:ident),+ })+) => {
+    $(
+      impl<$($p,)+ T> Clone for $name<$($p,)+ T>
+      where
+        $($p: Clone,)+
+        T: ?Sized,
+      {
+        fn clone(&self) -> Self {
+          $name {
+            $($field: self.$field.clone(),)+
+            value: PhantomData,
+          }
+        }
+      }
+
+      impl<$($p,)+ T> Copy for $name<$($p,)+ T>
+      where
+        $($p: Copy,)+
+        T: ?Sized,
+      {
+      }
+
+      impl<$($p,)+ T> Default for $name<$($p,)+ T>
+      where
+        $($p: Default,)+
+        T: ?Sized,
+      {
+        fn default() -> Self {
+          $name {
+            $($field: Default::default(),)+
+            value: PhantomData,
+          }
+        }
+      }
+
+      impl<$($p,)+ T> fmt::Debug for $name<$($p,)+ T>
+      where
+        $($p: fmt::Debug,)+
+        T: ?Sized,
+      {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+          f.debug_struct(stringify!($name))
+            $(.field(stringify!($field), &self.$field))+
+            .finish()
+        }
+      }
+    )+
+  };
+}
+
+/// Predicate satisfied if both sub-predicates are.
+///
+/// This is built by [`Predicate::and`].
+pub struct And<P, Q, T: ?Sized> {
+  lhs: P,
+  rhs: Q,
+  value: PhantomData<fn(&T)>,
+}
+
+impl<P, Q, T> Predicate<T> for And<P, Q, T>
+where
+  P: Predicate<T>,
+  Q: Predicate<T>,
+  T: ?Sized,
+{
+  fn test(&self, value: &T) -> bool {
+    self.lhs.test(value) && self.rhs.test(value)
+  }
+
+  fn describe(&self) -> String {
+    format!("{} and {}", self.lhs.describe(), self.rhs.describe())
+  }
+
+  fn explain(&self, value: &T) -> Option<String> {
+    self.lhs.explain(value).or_else(|| self.rhs.explain(value))
+  }
+}
+
+/// Predicate satisfied if either sub-predicate is.
+///
+/// This is built by [`Predicate::or`].
+pub struct Or<P, Q, T: ?Sized> {
+  lhs: P,
+  rhs: Q,
+  value: PhantomData<fn(&T)>,
+}
+
+impl<P, Q, T> Predicate<T> for Or<P, Q, T>
+where
+  P: Predicate<T>,
+  Q: Predicate<T>,
+  T: ?Sized,
+{
+  fn test(&self, value: &T) -> bool {
+    self.lhs.test(value) || self.rhs.test(value)
+  }
+
+  fn describe(&self) -> String {
+    format!("{} or {}", self.lhs.describe(), self.rhs.describe())
+  }
+
+  fn explain(&self, value: &T) -> Option<String> {
+    let lhs = self.lhs.explain(value)?;
+    let rhs = self.rhs.explain(value)?;
+    Some(format!("{} or {}", lhs, rhs))
+  }
+}
+
+/// Predicate satisfied if its sub-predicate isn’t.
+///
+/// This is built by [`Predicate::not`].
+pub struct Not<P, T: ?Sized> {
+  predicate: P,
+  value: PhantomData<fn(&T)>,
+}
+
+impl<P, T> Predicate<T> for Not<P, T>
+where
+  P: Predicate<T>,
+  T: ?Sized,
+{
+  fn test(&self, value: &T) -> bool {
+    !self.predicate.test(value)
+  }
+
+  fn describe(&self) -> String {
+    format!("not ({})", self.predicate.describe())
+  }
+}
+
+/// Predicate satisfied if a function of the value satisfies its sub-predicate.
+///
+/// This is built by [`Predicate::map`].
+pub struct Map<P, F, T: ?Sized> {
+  predicate: P,
+  f: F,
+  value: PhantomData<fn(&T)>,
+}
+
+impl<P, F, T, U> Predicate<U> for Map<P, F, T>
+where
+  P: Predicate<T>,
+  F: Fn(&U) -> T,
+  U: ?Sized,
+{
+  fn test(&self, value: &U) -> bool {
+    self.predicate.test(&(self.f)(value))
+  }
+
+  fn describe(&self) -> String {
+    self.predicate.describe()
+  }
+
+  fn explain(&self, value: &U) -> Option<String> {
+    self.predicate.explain(&(self.f)(value))
+  }
+}
+
+impl_combinator! {
+  And<P, Q> { lhs, rhs }
+  Or<P, Q> { lhs, rhs }
+  Not<P> { predicate }
+  Map<P, F> { predicate, f }
+}
+
+/// Value in `range`.
+pub fn in_range<R>(range: R) -> InRange<R> {
+  InRange(range)
+}
+
+/// Value between `min` and `max`, both included.
+pub fn between<T>(min: T, max: T) -> Between<T> {
+  Between(min, max)
+}
+
+/// Non-empty collection or string.
+pub fn non_empty() -> IsNonEmpty {
+  IsNonEmpty
+}
+
+/// Collection or string whose length is between `min` and `max`, both included.
+///
+/// The length of strings is their number of bytes.
+pub fn len_between(min: usize, max: usize) -> LenBetween {
+  LenBetween(min, max)
+}
+
+/// Sequence without duplicates.
+pub fn all_unique() -> AllUnique {
+  AllUnique
+}
+
+/// ASCII string.
+pub fn is_ascii() -> IsAscii {
+  IsAscii
+}
+
+/// String of at most `max` bytes.
+pub fn max_bytes(max: usize) -> MaxBytes {
+  MaxBytes(max)
+}
+
+/// String without control characters.
+pub fn no_control_chars() -> NoControlChars {
+  NoControlChars
+}
+
+/// String without leading or trailing whitespace.
+pub fn trimmed() -> IsTrimmed {
+  IsTrimmed
+}
+
+/// Finite floating-point number.
+pub fn finite() -> IsFinite {
+  IsFinite
+}
+
+/// Strictly positive number.
+pub fn positive() -> IsPositive {
+  IsPositive
+}
+
+/// Integer multiple of `n`.
+pub fn multiple_of<T>(n: T) -> MultipleOf<T> {
+  MultipleOf(n)
+}
+
+/// Integer between `MIN` and `MAX`, both included.
+///
+/// Unlike [`Between`], the bounds are part of the type, so that it can be used with
+/// [`Guarded`](crate::Guarded).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InBounds<const MIN: i128, const MAX: i128>;
+
+impl<T, const MIN: i128, const MAX: i128> Predicate<T> for InBounds<MIN, MAX>
+where
+  T: Copy + TryInto<i128>,
+{
+  fn test(&self, value: &T) -> bool {
+    (*value)
+      .try_into()
+      .map_or(false, |value| (MIN..=MAX).contains(&value))
+  }
+
+  fn describe(&self) -> String {
+    format!("between {} and {}", MIN, MAX)
+  }
+}
+
+/// Value in a range.
+///
+/// This is built by [`in_range`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InRange<R>(R);
+
+impl<T, R> Predicate<T> for InRange<R>
+where
+  T: PartialOrd,
+  R: RangeBounds<T> + fmt::Debug,
+{
+  fn test(&self, value: &T) -> bool {
+    self.0.contains(value)
+  }
+
+  fn describe(&self) -> String {
+    format!("in {:?}", self.0)
+  }
+}
+
+/// Value between two bounds, both included.
+///
+/// This is built by [`between`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Between<T>(T, T);
+
+impl<T> Predicate<T> for Between<T>
+where
+  T: PartialOrd + fmt::Display,
+{
+  fn test(&self, value: &T) -> bool {
+    self.0 <= *value && *value <= self.1
+  }
+
+  fn describe(&self) -> String {
+    format!("between {} and {}", self.0, self.1)
+  }
+}
+
+/// Non-empty collection or string.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IsNonEmpty;
+
+/// Collection or string whose length is between two bounds, both included.
+///
+/// This is built by [`len_between`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LenBetween(usize, usize);
+
+macro_rules! impl_len {
+  ($($t:ty $(, $p:tt)*;)+) => {
+    $(
+      impl<$($p),*> Predicate<$t> for IsNonEmpty {
+        fn test(&self, value: &$t) -> bool {
+          !value.is_empty()
+        }
+
+        fn describe(&self) -> String {
+          "non-empty".to_owned()
+        }
+      }
+
+      impl<$($p),*> Predicate<$t> for LenBetween {
+        fn test(&self, value: &$t) -> bool {
+          (self.0..=self.1).contains(&value.len())
+        }
+
+        fn describe(&self) -> String {
+          format!("of length between {} and {}", self.0, self.1)
+        }
+      }
+    )+
+  };
+}
+
+impl_len! {
+  str;
+  &'a str, 'a;
+  String;
+  [T], T;
+  &'a [T], 'a, T;
+  Vec<T>, T;
+  VecDeque<T>, T;
+  LinkedList<T>, T;
+  HashMap<K, V, S>, K, V, S;
+  HashSet<T, S>, T, S;
+  BTreeMap<K, V>, K, V;
+  BTreeSet<T>, T;
+}
+
+/// Sequence without duplicates.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AllUnique;
+
+macro_rules! impl_all_unique {
+  ($($t:ty $(, $p:tt)*;)+) => {
+    $(
+      impl<$($p,)* T> Predicate<$t> for AllUnique
+      where
+        T: Eq + Hash,
+      {
+        fn test(&self, value: &$t) -> bool {
+          let mut seen = HashSet::new();
+          value.iter().all(|item| seen.insert(item))
+        }
+
+        fn describe(&self) -> String {
+          "without duplicates".to_owned()
+        }
+      }
+    )+
+  };
+}
+
+impl_all_unique! {
+  [T];
+  &'a [T], 'a;
+  Vec<T>;
+  VecDeque<T>;
+  LinkedList<T>;
+}
+
+/// ASCII string.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IsAscii;
+
+impl<T> Predicate<T> for IsAscii
+where
+  T: ?Sized + AsRef<str>,
+{
+  fn test(&self, value: &T) -> bool {
+    value.as_ref().is_ascii()
+  }
+
+  fn describe(&self) -> String {
+    "ASCII".to_owned()
+  }
+}
+
+/// String of at most a given number of bytes.
+///
+/// This is built by [`max_bytes`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MaxBytes(usize);
+
+impl<T> Predicate<T> for MaxBytes
+where
+  T: ?Sized + AsRef<str>,
+{
+  fn test(&self, value: &T) -> bool {
+    value.as_ref().len() <= self.0
+  }
+
+  fn describe(&self) -> String {
+    format!("at most {} bytes", self.0)
+  }
+}
+
+/// String without control characters.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct NoControlChars;
+
+impl<T> Predicate<T> for NoControlChars
+where
+  T: ?Sized + AsRef<str>,
+{
+  fn test(&self, value: &T) -> bool {
+    !value.as_ref().chars().any(char::is_control)
+  }
+
+  fn describe(&self) -> String {
+    "without control characters".to_owned()
+  }
+}
+
+/// String without leading or trailing whitespace.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IsTrimmed;
+
+impl<T> Predicate<T> for IsTrimmed
+where
+  T: ?Sized + AsRef<str>,
+{
+  fn test(&self, value: &T) -> bool {
+    let value = value.as_ref();
+    value.trim() == value
+  }
+
+  fn describe(&self) -> String {
+    "without leading or trailing whitespace".to_owned()
+  }
+}
+
+/// Finite floating-point number.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IsFinite;
+
+impl Predicate<f32> for IsFinite {
+  fn test(&self, value: &f32) -> bool {
+    value.is_finite()
+  }
+
+  fn describe(&self) -> String {
+    "finite".to_owned()
+  }
+}
+
+impl Predicate<f64> for IsFinite {
+  fn test(&self, value: &f64) -> bool {
+    value.is_finite()
+  }
+
+  fn describe(&self) -> String {
+    "finite".to_owned()
+  }
+}
+
+/// Strictly positive number.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IsPositive;
+
+macro_rules! impl_positive {
+  ($($t:ty: $zero:expr),+) => {
+    $(
+      impl Predicate<$t> for IsPositive {
+        fn test(&self, value: &$t) -> bool {
+          *value > $zero
+        }
+
+        fn describe(&self) -> String {
+          "positive".to_owned()
+        }
+      }
+    )+
+  };
+}
+
+impl_positive! {
+  i8: 0, i16: 0, i32: 0, i64: 0, i128: 0, isize: 0,
+  u8: 0, u16: 0, u32: 0, u64: 0, u128: 0, usize: 0,
+  f32: 0., f64: 0.
+}
+
+/// Integer multiple of another one.
+///
+/// This is built by [`multiple_of`]. Only `0` is a multiple of `0`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MultipleOf<T>(T);
+
+macro_rules! impl_multiple_of {
+  ($($t:ty),+) => {
+    $(
+      impl Predicate<$t> for MultipleOf<$t> {
+        fn test(&self, value: &$t) -> bool {
+          if self.0 == 0 {
+            *value == 0
+          } else {
+            value.wrapping_rem(self.0) == 0
+          }
+        }
+
+        fn describe(&self) -> String {
+          format!("a multiple of {}", self.0)
+        }
+      }
+    )+
+  };
+}
+
+impl_multiple_of!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
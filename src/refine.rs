@@ -0,0 +1,225 @@
+//! Refinement types.
+
+use std::any::type_name;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::predicates::{InBounds, IsNonEmpty, IsPositive, Predicate};
+use crate::GuardError;
+
+/// Value of type `T` satisfying the predicate `P`.
+///
+/// A [`Guarded`] can only be built by [`Guarded::try_new`] — or [`TryFrom`], implemented for the
+/// standard types — which checks the predicate, and only gives shared access to the value, so that
+/// it never has to be validated again. `P` is typically a unit type describing the predicate, such
+/// as the ones of the [`predicates`](crate::predicates) module.
+///
+/// ```rust
+/// use try_guard::{Bounded, GuardError, NonEmpty};
+///
+/// struct User {
+///   name: NonEmpty<String>,
+///   age: Bounded<u8, 18, 130>,
+/// }
+///
+/// fn user(name: String, age: u8) -> Result<User, GuardError> {
+///   Ok(User {
+///     name: NonEmpty::try_new(name)?,
+///     age: Bounded::try_new(age)?,
+///   })
+/// }
+///
+/// let alice = user("Alice".to_owned(), 42).unwrap();
+/// assert_eq!(alice.name.len(), 5);
+/// assert_eq!(*alice.age, 42);
+///
+/// assert!(user(String::new(), 42).is_err());
+///
+/// let err = user("Bob".to_owned(), 12).err().unwrap();
+/// assert_eq!(err.reason(), Some("between 18 and 130"));
+/// ```
+pub struct Guarded<T, P> {
+  value: T,
+  predicate: PhantomData<fn() -> P>,
+}
+
+impl<T, P> Guarded<T, P>
+where
+  P: Predicate<T> + Default,
+{
+  /// Check that `value` satisfies the predicate.
+  ///
+  /// On failure, the [`GuardError`] gives the description of the failed part of the predicate as
+  /// [`GuardError::reason`], and the location of the caller — but not its module path.
+  #[track_caller]
+  pub fn try_new(value: T) -> Result<Self, GuardError> {
+    let reason = match P::default().explain(&value) {
+      Some(reason) => reason,
+      None => return Ok(Guarded::new_unchecked(value)),
+    };
+
+    Err(GuardError::at_caller(type_name::<P>()).with_reason(reason))
+  }
+}
+
+impl<T, P> Guarded<T, P> {
+  /// Wrap a value already known to satisfy the predicate.
+  pub(crate) fn new_unchecked(value: T) -> Self {
+    Guarded {
+      value,
+      predicate: PhantomData,
+    }
+  }
+
+  /// Value satisfying the predicate.
+  pub fn get(&self) -> &T {
+    &self.value
+  }
+
+  /// Give the value back.
+  pub fn into_inner(self) -> T {
+    self.value
+  }
+}
+
+impl<T, P> Deref for Guarded<T, P> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.value
+  }
+}
+
+impl<T, P, U> AsRef<U> for Guarded<T, P>
+where
+  T: AsRef<U>,
+  U: ?Sized,
+{
+  fn as_ref(&self) -> &U {
+    self.value.as_ref()
+  }
+}
+
+// a blanket TryFrom<T> implementation would conflict with the one of core
+macro_rules! impl_try_from {
+  ($($t:ty $(, $p:tt)*;)+) => {
+    $(
+      impl<$($p,)* P> TryFrom<$t> for Guarded<$t, P>
+      where
+        P: Predicate<$t> + Default,
+      {
+        type Error = GuardError;
+
+        #[track_caller]
+        fn try_from(value: $t) -> Result<Self, GuardError> {
+          Guarded::try_new(value)
+        }
+      }
+    )+
+  };
+}
+
+impl_try_from! {
+  i8; i16; i32; i64; i128; isize;
+  u8; u16; u32; u64; u128; usize;
+  f32; f64; bool; char;
+  String;
+  &'a str, 'a;
+  &'a [T], 'a, T;
+  Box<str>;
+  Box<[T]>, T;
+  Vec<T>, T;
+  VecDeque<T>, T;
+  LinkedList<T>, T;
+  HashMap<K, V, S>, K, V, S;
+  HashSet<T, S>, T, S;
+  BTreeMap<K, V>, K, V;
+  BTreeSet<T>, T;
+}
+
+impl<T, P> Clone for Guarded<T, P>
+where
+  T: Clone,
+{
+  fn clone(&self) -> Self {
+    Guarded {
+      value: self.value.clone(),
+      predicate: PhantomData,
+    }
+  }
+}
+
+impl<T, P> Copy for Guarded<T, P> where T: Copy {}
+
+impl<T, P> fmt::Debug for Guarded<T, P>
+where
+  T: fmt::Debug,
+{
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.value.fmt(f)
+  }
+}
+
+impl<T, P> fmt::Display for Guarded<T, P>
+where
+  T: fmt::Display,
+{
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.value.fmt(f)
+  }
+}
+
+impl<T, P> PartialEq for Guarded<T, P>
+where
+  T: PartialEq,
+{
+  fn eq(&self, rhs: &Self) -> bool {
+    self.value == rhs.value
+  }
+}
+
+impl<T, P> Eq for Guarded<T, P> where T: Eq {}
+
+impl<T, P> PartialOrd for Guarded<T, P>
+where
+  T: PartialOrd,
+{
+  fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+    self.value.partial_cmp(&rhs.value)
+  }
+}
+
+impl<T, P> Ord for Guarded<T, P>
+where
+  T: Ord,
+{
+  fn cmp(&self, rhs: &Self) -> Ordering {
+    self.value.cmp(&rhs.value)
+  }
+}
+
+impl<T, P> Hash for Guarded<T, P>
+where
+  T: Hash,
+{
+  fn hash<H>(&self, state: &mut H)
+  where
+    H: Hasher,
+  {
+    self.value.hash(state)
+  }
+}
+
+/// Non-empty collection or string.
+pub type NonEmpty<T> = Guarded<T, IsNonEmpty>;
+
+/// Strictly positive number.
+pub type Positive<T> = Guarded<T, IsPositive>;
+
+/// Integer between `MIN` and `MAX`, both included.
+pub type Bounded<T, const MIN: i128, const MAX: i128> = Guarded<T, InBounds<MIN, MAX>>;
@@ -0,0 +1,370 @@
+//! Power-assert style reporting of failed guards.
+
+use std::fmt;
+
+/// Version of [`guard!`] that reports a [`GuardError`].
+///
+/// If the predicate is `false`, the current function early-returns with a [`GuardError`] capturing
+/// the predicate and the location of the call site.
+///
+/// ```rust
+/// use try_guard::{guard_report, GuardError};
+///
+/// fn foo(x: u32) -> Result<u32, GuardError> {
+///   guard_report!(x % 2 == 0);
+///   Ok(x / 2)
+/// }
+///
+/// let err = foo(3).unwrap_err();
+/// assert_eq!(err.expr(), "x % 2 == 0");
+/// assert!(err.to_string().starts_with("guard failed: `x % 2 == 0` at "));
+/// ```
+///
+/// # Operands
+///
+/// Simple predicates are decomposed so that the [`GuardError`] also captures the runtime value of
+/// their sub-expressions — see [`GuardError::operands`]:
+///
+///   - Comparisons — `a == b`, `a != b`, `a < b`, `a <= b`, `a > b` and `a >= b` — capture both
+///     operands. Each operand is evaluated exactly once.
+///   - Method calls on a variable or a field — `x.is_empty()`, `self.items.contains(&y)` — capture
+///     the receiver. Calls to methods of the standard library taking their receiver by value, such
+///     as `x.map_or(…)` or `x.is_some_and(…)`, are not decomposed.
+///
+/// Operands are only captured once the predicate is known to be `false`, so that passing guards
+/// don’t format anything.
+///
+/// Operands are captured with their [`Debug`](fmt::Debug) implementation; operands not
+/// implementing it are captured without a value.
+///
+/// ```rust
+/// use try_guard::{guard_report, GuardError};
+///
+/// fn foo(a: &[u32], limit: usize) -> Result<(), GuardError> {
+///   guard_report!(a.len() < limit);
+///   Ok(())
+/// }
+///
+/// let err = foo(&[1, 2, 3], 2).unwrap_err();
+/// assert_eq!(err.operands()[0].expr(), "a.len()");
+/// assert_eq!(err.operands()[0].value(), Some("3"));
+/// assert_eq!(err.operands()[1].expr(), "limit");
+/// assert_eq!(err.operands()[1].value(), Some("2"));
+/// ```
+///
+/// Predicates that are not simple enough — e.g. using `&&`, `||`, closures, casts or turbofishes at
+/// the top-level — are reported without operands. You can also opt out of the decomposition by
+/// wrapping the predicate in parentheses, which you have to do when a method of your own consumes
+/// a receiver that isn’t [`Copy`]:
+///
+/// ```rust
+/// use try_guard::{guard_report, GuardError};
+///
+/// struct Token(String);
+///
+/// impl Token {
+///   fn is_valid(self) -> bool {
+///     !self.0.is_empty()
+///   }
+/// }
+///
+/// fn foo(token: Token) -> Result<(), GuardError> {
+///   guard_report!((token.is_valid()));
+///   Ok(())
+/// }
+///
+/// assert!(foo(Token(String::new())).unwrap_err().operands().is_empty());
+/// ```
+///
+/// # Predicates
+///
+/// `guard_report!(value => predicate)` checks that `value` satisfies a [`Predicate`]. On failure,
+/// the [`GuardError`] captures `value` and gives the description of the failed part of the
+/// predicate as [`GuardError::reason`]:
+///
+/// ```rust
+/// use try_guard::predicates::{InBounds, IsNonEmpty};
+/// use try_guard::{guard_report, GuardError, Predicate};
+///
+/// fn foo(xs: Vec<u8>) -> Result<(), GuardError> {
+///   guard_report!(xs => IsNonEmpty.and(InBounds::<1, 3>.map(|xs: &Vec<u8>| xs.len())));
+///   Ok(())
+/// }
+///
+/// let err = foo(vec![1, 2, 3, 4]).unwrap_err();
+/// assert_eq!(err.reason(), Some("between 1 and 3"));
+/// assert_eq!(err.operands()[0].value(), Some("[1, 2, 3, 4]"));
+/// ```
+///
+/// [`guard!`]: crate::guard
+/// [`GuardError`]: crate::GuardError
+/// [`GuardError::operands`]: crate::GuardError::operands
+/// [`GuardError::reason`]: crate::GuardError::reason
+/// [`Predicate`]: crate::Predicate
+#[macro_export]
+macro_rules! guard_report {
+  ($e:expr,) => {
+    $crate::guard_report!($e)
+  };
+
+  ($($t:tt)+) => {
+    $crate::__guard_report!({$crate::__guard_fail!} @scan [$($t)+] [] $($t)+)
+  };
+}
+
+/// Decompose the predicate of a [`guard_report!`] and pass a [`GuardError`] to the failure
+/// callback — the first argument — if it’s `false`.
+///
+/// The predicate is scanned token by token, looking for a top-level comparison operator. If none
+/// is found, the predicate is checked for a method call on a place. Anything that could make that
+/// decomposition wrong — lower-precedence operators, closures, casts, generic arguments — falls
+/// back to the plain predicate.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guard_report {
+  // Predicate checked against a value.
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($value:tt)+] => $($predicate:tt)+) => {
+    match &($($value)+) {
+      __guard_value => {
+        if let ::core::option::Option::Some(reason) =
+          $crate::Predicate::explain(&($($predicate)+), __guard_value)
+        {
+          $($fail)+($crate::__guard_report!({$($fail)+} @error [$($all)+])
+            .with_reason(reason)
+            .with_operand(stringify!($($value)+), $crate::__guard_capture!(__guard_value)))
+        }
+      }
+    }
+  };
+
+  // Comparison operators; the left operand must not be empty.
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)+] == $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @rhs [$($all)+] [$($lhs)+] [==] [] $($rest)+)
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)+] != $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @rhs [$($all)+] [$($lhs)+] [!=] [] $($rest)+)
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)+] <= $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @rhs [$($all)+] [$($lhs)+] [<=] [] $($rest)+)
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)+] >= $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @rhs [$($all)+] [$($lhs)+] [>=] [] $($rest)+)
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)+] < $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @rhs [$($all)+] [$($lhs)+] [<] [] $($rest)+)
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)+] > $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @rhs [$($all)+] [$($lhs)+] [>] [] $($rest)+)
+  };
+
+  // Tokens we can’t safely decompose around.
+  ({$($fail:tt)+} @scan [$($all:tt)+] [] < $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] :: < $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] && $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] || $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] | $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] .. $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] ..= $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] = $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] as $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] let $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] return $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] break $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)*] $t:tt $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @scan [$($all)+] [$($lhs)* $t] $($rest)*)
+  };
+
+  // No comparison; look for a method call instead.
+  ({$($fail:tt)+} @scan [$($all:tt)+] [$($lhs:tt)+]) => {
+    $crate::__guard_report!({$($fail)+} @method [$($all)+] [] $($lhs)+)
+  };
+
+  // Right operand of a comparison.
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] :: < $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] && $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] || $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] | $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] .. $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] ..= $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] = $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] as $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)*] $t:tt $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @rhs [$($all)+] [$($lhs)+] [$op] [$($rhs)* $t] $($rest)*)
+  };
+  ({$($fail:tt)+} @rhs [$($all:tt)+] [$($lhs:tt)+] [$op:tt] [$($rhs:tt)+]) => {
+    $crate::__guard_cmp!({$($fail)+} [$($all)+] $op, $($lhs)+, $($rhs)+)
+  };
+
+  // Method call at the end of the predicate.
+  ({$($fail:tt)+} @method [$($all:tt)+] [$($recv:tt)+] . $m:ident ($($args:tt)*)) => {
+    $crate::__guard_by_value!(
+      $m,
+      $crate::__guard_report!({$($fail)+} @plain [$($all)+]),
+      $crate::__guard_report!({$($fail)+} @place [$($all)+] [$($recv)+] $($recv)+)
+    )
+  };
+  ({$($fail:tt)+} @method [$($all:tt)+] [$($recv:tt)*] $t:tt $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @method [$($all)+] [$($recv)* $t] $($rest)*)
+  };
+  ({$($fail:tt)+} @method [$($all:tt)+] [$($recv:tt)*]) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+
+  // The receiver must be a place — a variable, possibly followed by fields — so that it can be
+  // borrowed again once the predicate is evaluated.
+  ({$($fail:tt)+} @place [$($all:tt)+] [$($recv:tt)+] $i:ident) => {
+    if !($($all)+) {
+      $($fail)+($crate::__guard_report!({$($fail)+} @error [$($all)+])
+        .with_operand(stringify!($($recv)+), $crate::__guard_capture!(&$($recv)+)))
+    }
+  };
+  ({$($fail:tt)+} @place [$($all:tt)+] [$($recv:tt)+] $i:ident . $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @field [$($all)+] [$($recv)+] $($rest)+)
+  };
+  ({$($fail:tt)+} @place [$($all:tt)+] [$($recv:tt)+] $($rest:tt)*) => {
+    $crate::__guard_report!({$($fail)+} @plain [$($all)+])
+  };
+  ({$($fail:tt)+} @field [$($all:tt)+] [$($recv:tt)+] $i:literal) => {
+    $crate::__guard_report!({$($fail)+} @place [$($all)+] [$($recv)+] self)
+  };
+  ({$($fail:tt)+} @field [$($all:tt)+] [$($recv:tt)+] $i:literal . $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @place [$($all)+] [$($recv)+] self . $($rest)+)
+  };
+  ({$($fail:tt)+} @field [$($all:tt)+] [$($recv:tt)+] $($rest:tt)+) => {
+    $crate::__guard_report!({$($fail)+} @place [$($all)+] [$($recv)+] $($rest)+)
+  };
+
+  // Predicate reported without operands.
+  ({$($fail:tt)+} @plain [$($all:tt)+]) => {
+    if !($($all)+) {
+      $($fail)+($crate::__guard_report!({$($fail)+} @error [$($all)+]))
+    }
+  };
+
+  ({$($fail:tt)+} @error [$($all:tt)+]) => {
+    $crate::GuardError::new(
+      stringify!($($all)+),
+      file!(),
+      line!(),
+      column!(),
+      module_path!(),
+    )
+  };
+}
+
+/// Expand to `by_value` if `method` is a method of the standard library which may take its
+/// receiver by value and return a [`bool`], and to `by_ref` otherwise.
+///
+/// Iterator methods are included, even though the slice methods of the same name borrow.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guard_by_value {
+  (is_some_and, $by_value:expr, $by_ref:expr) => { $by_value };
+  (is_none_or, $by_value:expr, $by_ref:expr) => { $by_value };
+  (is_ok_and, $by_value:expr, $by_ref:expr) => { $by_value };
+  (is_err_and, $by_value:expr, $by_ref:expr) => { $by_value };
+  (map_or, $by_value:expr, $by_ref:expr) => { $by_value };
+  (map_or_else, $by_value:expr, $by_ref:expr) => { $by_value };
+  (unwrap, $by_value:expr, $by_ref:expr) => { $by_value };
+  (expect, $by_value:expr, $by_ref:expr) => { $by_value };
+  (unwrap_or, $by_value:expr, $by_ref:expr) => { $by_value };
+  (unwrap_or_else, $by_value:expr, $by_ref:expr) => { $by_value };
+  (unwrap_or_default, $by_value:expr, $by_ref:expr) => { $by_value };
+  (eq, $by_value:expr, $by_ref:expr) => { $by_value };
+  (ne, $by_value:expr, $by_ref:expr) => { $by_value };
+  (lt, $by_value:expr, $by_ref:expr) => { $by_value };
+  (le, $by_value:expr, $by_ref:expr) => { $by_value };
+  (gt, $by_value:expr, $by_ref:expr) => { $by_value };
+  (ge, $by_value:expr, $by_ref:expr) => { $by_value };
+  (eq_by, $by_value:expr, $by_ref:expr) => { $by_value };
+  (is_sorted, $by_value:expr, $by_ref:expr) => { $by_value };
+  (is_sorted_by, $by_value:expr, $by_ref:expr) => { $by_value };
+  (is_sorted_by_key, $by_value:expr, $by_ref:expr) => { $by_value };
+  (is_partitioned, $by_value:expr, $by_ref:expr) => { $by_value };
+  ($m:ident, $by_value:expr, $by_ref:expr) => { $by_ref };
+}
+
+/// Capture the [`Debug`](fmt::Debug) representation of a reference, if any.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guard_capture {
+  ($e:expr) => {{
+    #[allow(unused_imports)]
+    use $crate::__private::{CaptureDebug as _, CaptureFallback as _};
+    (&$crate::__private::Capture($e)).capture()
+  }};
+}
+
+/// Reference to an operand to capture.
+///
+/// [`CaptureDebug`] is implemented on `Capture` when the operand implements
+/// [`Debug`](fmt::Debug), and [`CaptureFallback`] on `&Capture` otherwise; autoref-based method
+/// resolution picks the former when possible.
+pub struct Capture<'a, T: ?Sized>(pub &'a T);
+
+pub trait CaptureDebug {
+  fn capture(&self) -> Option<String>;
+}
+
+impl<T> CaptureDebug for Capture<'_, T>
+where
+  T: ?Sized + fmt::Debug,
+{
+  fn capture(&self) -> Option<String> {
+    Some(format!("{:?}", self.0))
+  }
+}
+
+pub trait CaptureFallback {
+  fn capture(&self) -> Option<String>;
+}
+
+impl<T> CaptureFallback for &Capture<'_, T>
+where
+  T: ?Sized,
+{
+  fn capture(&self) -> Option<String> {
+    None
+  }
+}
@@ -0,0 +1,121 @@
+//! [serde] integration.
+//!
+//! With the `serde` feature, [`Guarded`] implements [`Serialize`] and [`Deserialize`]
+//! transparently, checking its predicate while deserializing, so that invalid values are reported
+//! as deserialization errors:
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use try_guard::{Bounded, NonEmpty};
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!   name: NonEmpty<String>,
+//!   workers: Bounded<u8, 1, 64>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{ "name": "app", "workers": 8 }"#).unwrap();
+//! assert_eq!(*config.workers, 8);
+//!
+//! assert!(serde_json::from_str::<Config>(r#"{ "name": "", "workers": 8 }"#).is_err());
+//! assert!(serde_json::from_str::<Config>(r#"{ "name": "app", "workers": 0 }"#).is_err());
+//! ```
+//!
+//! Fields of plain types can be checked as well, with the `deserialize_with` helpers of this
+//! module:
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use try_guard::predicates::IsNonEmpty;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!   #[serde(deserialize_with = "try_guard::serde::guarded::<_, IsNonEmpty, _>")]
+//!   name: String,
+//!   #[serde(deserialize_with = "try_guard::serde::bounded::<_, 1, 64, _>")]
+//!   workers: u8,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{ "name": "app", "workers": 8 }"#).unwrap();
+//! assert_eq!(config.workers, 8);
+//!
+//! assert!(serde_json::from_str::<Config>(r#"{ "name": "app", "workers": 65 }"#).is_err());
+//! ```
+//!
+//! [serde]: https://serde.rs
+
+use ::serde::de::{Deserialize, Deserializer, Error};
+use ::serde::ser::{Serialize, Serializer};
+
+use crate::predicates::{InBounds, IsNonEmpty, IsPositive, Predicate};
+use crate::refine::Guarded;
+
+impl<T, P> Serialize for Guarded<T, P>
+where
+  T: Serialize,
+{
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    self.get().serialize(serializer)
+  }
+}
+
+impl<'de, T, P> Deserialize<'de> for Guarded<T, P>
+where
+  T: Deserialize<'de>,
+  P: Predicate<T> + Default,
+{
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let value = T::deserialize(deserializer)?;
+
+    match P::default().explain(&value) {
+      Some(reason) => Err(D::Error::custom(format_args!("expected {}", reason))),
+      None => Ok(Guarded::new_unchecked(value)),
+    }
+  }
+}
+
+/// Deserialize a value and check that it satisfies the predicate `P`.
+pub fn guarded<'de, T, P, D>(deserializer: D) -> Result<T, D::Error>
+where
+  T: Deserialize<'de>,
+  P: Predicate<T> + Default,
+  D: Deserializer<'de>,
+{
+  Guarded::<T, P>::deserialize(deserializer).map(Guarded::into_inner)
+}
+
+/// Deserialize a non-empty collection or string.
+pub fn non_empty<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+  T: Deserialize<'de>,
+  IsNonEmpty: Predicate<T>,
+  D: Deserializer<'de>,
+{
+  guarded::<T, IsNonEmpty, D>(deserializer)
+}
+
+/// Deserialize a strictly positive number.
+pub fn positive<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+  T: Deserialize<'de>,
+  IsPositive: Predicate<T>,
+  D: Deserializer<'de>,
+{
+  guarded::<T, IsPositive, D>(deserializer)
+}
+
+/// Deserialize an integer between `MIN` and `MAX`, both included.
+pub fn bounded<'de, T, const MIN: i128, const MAX: i128, D>(deserializer: D) -> Result<T, D::Error>
+where
+  T: Deserialize<'de>,
+  InBounds<MIN, MAX>: Predicate<T>,
+  D: Deserializer<'de>,
+{
+  guarded::<T, InBounds<MIN, MAX>, D>(deserializer)
+}
@@ -0,0 +1,362 @@
+//! Error-accumulating validation.
+
+use std::borrow::Cow;
+
+use crate::path::{Path, PathSegment, PrefixPath};
+use crate::GuardError;
+
+/// Error-accumulating validator.
+///
+/// Instead of early-returning on the first failed guard, a [`Validator`] records every failure —
+/// see [`check!`] — so that they can be reported all at once with [`Validator::finish`].
+///
+/// [`check!`]: crate::check
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Validator<E = GuardError> {
+  errors: Vec<E>,
+}
+
+impl<E> Validator<E> {
+  /// Create a [`Validator`] without any failure.
+  pub fn new() -> Self {
+    Validator { errors: Vec::new() }
+  }
+
+  /// Record a failure.
+  pub fn fail<F>(&mut self, failure: F)
+  where
+    F: Into<E>,
+  {
+    self.errors.push(failure.into());
+  }
+
+  /// Whether no failure was recorded so far.
+  pub fn is_valid(&self) -> bool {
+    self.errors.is_empty()
+  }
+
+  /// Failures recorded so far.
+  pub fn errors(&self) -> &[E] {
+    &self.errors
+  }
+
+  /// Finish validating, giving all the recorded failures, if any.
+  pub fn finish(self) -> Result<(), Vec<E>> {
+    if self.errors.is_empty() {
+      Ok(())
+    } else {
+      Err(self.errors)
+    }
+  }
+}
+
+impl<E> Default for Validator<E> {
+  fn default() -> Self {
+    Validator::new()
+  }
+}
+
+/// Path-aware [`Validator`], to validate nested structures.
+///
+/// A [`ValidationContext`] tracks the [`Path`] of the value being validated: [`field`] and
+/// [`index`] run a closure with a new segment appended to it. Failures recorded with [`check!`]
+/// get the current path attached, and failures returned by the closure — e.g. by [`guard_report!`]
+/// or [`guard_eq!`] — get the segment prepended.
+///
+/// ```rust
+/// use try_guard::{check, guard_report, GuardError, ValidationContext};
+///
+/// struct Address {
+///   zip: String,
+/// }
+///
+/// struct User {
+///   name: String,
+///   addresses: Vec<Address>,
+/// }
+///
+/// fn validate(user: &User) -> Result<(), Vec<GuardError>> {
+///   let mut ctx = ValidationContext::new();
+///
+///   ctx.field("name", |ctx| check!(ctx, !user.name.is_empty()));
+///   ctx.field("addresses", |ctx| {
+///     for (i, address) in user.addresses.iter().enumerate() {
+///       let r = ctx.index(i, |ctx| {
+///         ctx.field("zip", |_| -> Result<(), GuardError> {
+///           guard_report!(address.zip.len() == 5);
+///           Ok(())
+///         })
+///       });
+///
+///       if let Err(err) = r {
+///         ctx.fail(err);
+///       }
+///     }
+///   });
+///
+///   ctx.finish()
+/// }
+///
+/// let user = User {
+///   name: "Alice".to_owned(),
+///   addresses: vec![
+///     Address { zip: "12345".to_owned() },
+///     Address { zip: "123".to_owned() },
+///   ],
+/// };
+///
+/// let errors = validate(&user).unwrap_err();
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].path().to_string(), "addresses[1].zip");
+/// assert_eq!(errors[0].path().to_json_pointer(), "/addresses/1/zip");
+/// ```
+///
+/// [`field`]: ValidationContext::field
+/// [`index`]: ValidationContext::index
+/// [`check!`]: crate::check
+/// [`guard_eq!`]: crate::guard_eq
+/// [`guard_report!`]: crate::guard_report
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ValidationContext<E = GuardError> {
+  validator: Validator<E>,
+  path: Path,
+}
+
+impl<E> ValidationContext<E> {
+  /// Create a [`ValidationContext`] located at the root value, without any failure.
+  pub fn new() -> Self {
+    ValidationContext {
+      validator: Validator::new(),
+      path: Path::new(),
+    }
+  }
+
+  /// Current path.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Validate the field `name` of the current value with `f`.
+  pub fn field<N, F, R>(&mut self, name: N, f: F) -> R
+  where
+    N: Into<Cow<'static, str>>,
+    F: FnOnce(&mut Self) -> R,
+    R: PrefixPath,
+  {
+    self.scoped(PathSegment::Field(name.into()), f)
+  }
+
+  /// Validate the element at `index` of the current value with `f`.
+  pub fn index<F, R>(&mut self, index: usize, f: F) -> R
+  where
+    F: FnOnce(&mut Self) -> R,
+    R: PrefixPath,
+  {
+    self.scoped(PathSegment::Index(index), f)
+  }
+
+  fn scoped<F, R>(&mut self, segment: PathSegment, f: F) -> R
+  where
+    F: FnOnce(&mut Self) -> R,
+    R: PrefixPath,
+  {
+    self.path.push(segment);
+    let r = f(self);
+    let segment = self.path.pop();
+
+    r.prefix_path(segment.as_ref().map_or(&[], std::slice::from_ref))
+  }
+
+  /// Record a failure, attaching the current path to it.
+  pub fn fail<F>(&mut self, failure: F)
+  where
+    F: Into<E>,
+    E: PrefixPath,
+  {
+    let failure = failure.into().prefix_path(self.path.segments());
+    self.validator.fail(failure);
+  }
+
+  /// Whether no failure was recorded so far.
+  pub fn is_valid(&self) -> bool {
+    self.validator.is_valid()
+  }
+
+  /// Failures recorded so far.
+  pub fn errors(&self) -> &[E] {
+    self.validator.errors()
+  }
+
+  /// Finish validating, giving all the recorded failures, if any.
+  pub fn finish(self) -> Result<(), Vec<E>> {
+    self.validator.finish()
+  }
+}
+
+impl<E> Default for ValidationContext<E> {
+  fn default() -> Self {
+    ValidationContext::new()
+  }
+}
+
+/// Validation of a value.
+///
+/// A type implementing [`Validate`] can be validated in two modes: [`validate`] stops at the
+/// first failure, while [`validate_all`] reports them all. Failures are [`GuardError`]s, with the
+/// [`Path`] of the invalid value attached.
+///
+/// Implementors only have to provide [`validate_in`], which records failures in a
+/// [`ValidationContext`]. Most of the time, you want to derive it from `#[guard(…)]` field
+/// attributes instead — see [`Validate`](macro@crate::Validate).
+///
+/// [`validate`]: Validate::validate
+/// [`validate_all`]: Validate::validate_all
+/// [`validate_in`]: Validate::validate_in
+pub trait Validate {
+  /// Validate, stopping at the first failure.
+  ///
+  /// The default implementation records all failures and only gives the first one back; override
+  /// it if that’s too costly.
+  fn validate(&self) -> Result<(), GuardError> {
+    self
+      .validate_all()
+      .map_err(|errors| errors.into_iter().next().unwrap())
+  }
+
+  /// Validate, recording every failure in `ctx`.
+  fn validate_in(&self, ctx: &mut ValidationContext);
+
+  /// Validate, giving all the failures, if any.
+  fn validate_all(&self) -> Result<(), Vec<GuardError>> {
+    let mut ctx = ValidationContext::new();
+    self.validate_in(&mut ctx);
+    ctx.finish()
+  }
+}
+
+impl<T> Validate for &T
+where
+  T: ?Sized + Validate,
+{
+  fn validate(&self) -> Result<(), GuardError> {
+    T::validate(self)
+  }
+
+  fn validate_in(&self, ctx: &mut ValidationContext) {
+    T::validate_in(self, ctx)
+  }
+}
+
+impl<T> Validate for Box<T>
+where
+  T: ?Sized + Validate,
+{
+  fn validate(&self) -> Result<(), GuardError> {
+    T::validate(self)
+  }
+
+  fn validate_in(&self, ctx: &mut ValidationContext) {
+    T::validate_in(self, ctx)
+  }
+}
+
+impl<T> Validate for Option<T>
+where
+  T: Validate,
+{
+  fn validate(&self) -> Result<(), GuardError> {
+    self.as_ref().map_or(Ok(()), T::validate)
+  }
+
+  fn validate_in(&self, ctx: &mut ValidationContext) {
+    if let Some(value) = self {
+      value.validate_in(ctx);
+    }
+  }
+}
+
+impl<T> Validate for [T]
+where
+  T: Validate,
+{
+  fn validate(&self) -> Result<(), GuardError> {
+    for (i, value) in self.iter().enumerate() {
+      value
+        .validate()
+        .map_err(|err| err.prefix_path(&[PathSegment::Index(i)]))?;
+    }
+
+    Ok(())
+  }
+
+  fn validate_in(&self, ctx: &mut ValidationContext) {
+    for (i, value) in self.iter().enumerate() {
+      ctx.index(i, |ctx| value.validate_in(ctx));
+    }
+  }
+}
+
+impl<T> Validate for Vec<T>
+where
+  T: Validate,
+{
+  fn validate(&self) -> Result<(), GuardError> {
+    self.as_slice().validate()
+  }
+
+  fn validate_in(&self, ctx: &mut ValidationContext) {
+    self.as_slice().validate_in(ctx)
+  }
+}
+
+/// Error-accumulating version of [`guard!`].
+///
+/// `check!(validator, cond)` records a failure in `validator` — a [`Validator`] or a
+/// [`ValidationContext`] — if `cond` is `false`, instead of early-returning. The failure is a
+/// [`GuardError`], with the same operands capture as [`guard_report!`], converted with [`From`].
+/// As with [`guard!`], an error can be passed as last argument instead; it’s only evaluated if
+/// `cond` is `false`.
+///
+/// ```rust
+/// use try_guard::{check, GuardError, Validator};
+///
+/// struct Config {
+///   name: String,
+///   workers: u32,
+/// }
+///
+/// fn validate(config: &Config) -> Result<(), Vec<GuardError>> {
+///   let mut validator = Validator::new();
+///
+///   check!(validator, !config.name.is_empty());
+///   check!(validator, config.workers > 0);
+///   check!(validator, config.workers <= 64);
+///
+///   validator.finish()
+/// }
+///
+/// let config = Config { name: String::new(), workers: 0 };
+/// let errors = validate(&config).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].expr(), "!config.name.is_empty()");
+/// assert_eq!(errors[1].expr(), "config.workers > 0");
+/// ```
+///
+/// [`guard!`]: crate::guard
+/// [`guard_report!`]: crate::guard_report
+#[macro_export]
+macro_rules! check {
+  ($v:expr, $e:expr, $err:expr $(,)?) => {
+    if !$e {
+      $v.fail($err);
+    }
+  };
+
+  ($v:expr, $e:expr,) => {
+    $crate::check!($v, $e)
+  };
+
+  ($v:expr, $($t:tt)+) => {
+    $crate::__guard_report!({$v.fail} @scan [$($t)+] [] $($t)+)
+  };
+}
@@ -0,0 +1,78 @@
+use try_guard::{check, GuardError, Validator};
+
+#[test]
+fn valid() {
+  let mut validator: Validator = Validator::new();
+  check!(validator, 1 < 2);
+  check!(validator, "foo".starts_with('f'));
+
+  assert!(validator.is_valid());
+  assert_eq!(validator.finish(), Ok(()));
+}
+
+#[test]
+fn accumulate() {
+  let (a, b) = (3, 2);
+  let mut validator: Validator = Validator::new();
+  check!(validator, a < b);
+  check!(validator, a == 3);
+  check!(validator, a + b > 10);
+
+  assert!(!validator.is_valid());
+
+  let errors = validator.finish().unwrap_err();
+  assert_eq!(errors.len(), 2);
+  assert_eq!(errors[0].expr(), "a < b");
+  assert_eq!(errors[0].operands()[0].value(), Some("3"));
+  assert_eq!(errors[0].operands()[1].value(), Some("2"));
+  assert_eq!(errors[1].expr(), "a + b > 10");
+}
+
+#[test]
+fn custom_error() {
+  let mut evaluated = false;
+  let mut validator = Validator::<String>::new();
+  check!(validator, 1 > 2, "first");
+  check!(validator, 1 < 2, {
+    evaluated = true;
+    "second"
+  });
+  check!(validator, 2 > 3, format!("{} > {}", 2, 3));
+
+  assert!(!evaluated);
+  assert_eq!(
+    validator.finish(),
+    Err(vec!["first".to_owned(), "2 > 3".to_owned()])
+  );
+}
+
+#[derive(Debug, PartialEq)]
+enum ConfigError {
+  Guard(&'static str),
+}
+
+impl From<GuardError> for ConfigError {
+  fn from(err: GuardError) -> Self {
+    ConfigError::Guard(err.expr())
+  }
+}
+
+#[test]
+fn into_custom_error() {
+  fn validate(validator: &mut Validator<ConfigError>, workers: u32) {
+    check!(validator, workers > 0);
+    check!(validator, workers <= 64);
+  }
+
+  let mut validator = Validator::new();
+  validate(&mut validator, 0);
+  validate(&mut validator, 100);
+
+  assert_eq!(
+    validator.errors(),
+    &[
+      ConfigError::Guard("workers > 0"),
+      ConfigError::Guard("workers <= 64")
+    ]
+  );
+}
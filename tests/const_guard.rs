@@ -0,0 +1,35 @@
+use try_guard::{const_guard, static_guard};
+
+const fn checked_sub(a: u32, b: u32) -> Option<u32> {
+  const_guard!(a >= b);
+  Some(a - b)
+}
+
+const fn parse_digit(c: u8) -> Result<u8, u8> {
+  const_guard!(c.is_ascii_digit(), c);
+  Ok(c - b'0')
+}
+
+const DIGITS: [Result<u8, u8>; 2] = [parse_digit(b'7'), parse_digit(b'x')];
+
+static_guard!(checked_sub(3, 2).is_some());
+static_guard!(DIGITS.len() == 2, "two digits");
+
+#[test]
+fn option() {
+  const OK: Option<u32> = checked_sub(3, 1);
+  const KO: Option<u32> = checked_sub(1, 3);
+
+  assert_eq!(OK, Some(2));
+  assert_eq!(KO, None);
+}
+
+#[test]
+fn result() {
+  assert_eq!(DIGITS, [Ok(7), Err(b'x')]);
+}
+
+#[test]
+fn static_guard_in_fn() {
+  static_guard!(u8::MAX as u32 + 1 == 256);
+}
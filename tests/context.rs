@@ -0,0 +1,96 @@
+use try_guard::{check, guard_eq, guard_report, GuardError, PathSegment, ValidationContext};
+
+#[test]
+fn root_path() {
+  let mut ctx: ValidationContext = ValidationContext::new();
+  check!(ctx, 1 > 2);
+
+  let errors = ctx.finish().unwrap_err();
+  assert!(errors[0].path().is_empty());
+  assert_eq!(errors[0].path().to_json_pointer(), "");
+}
+
+#[test]
+fn check_nested_path() {
+  let zips = ["12345", "123", "1"];
+  let mut ctx: ValidationContext = ValidationContext::new();
+
+  ctx.field("user", |ctx| {
+    ctx.field("addresses", |ctx| {
+      for (i, zip) in zips.iter().enumerate() {
+        ctx.index(i, |ctx| ctx.field("zip", |ctx| check!(ctx, zip.len() == 5)));
+      }
+    })
+  });
+
+  assert!(ctx.path().is_empty());
+
+  let errors = ctx.finish().unwrap_err();
+  let paths = errors
+    .iter()
+    .map(|err| err.path().to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    paths,
+    vec!["user.addresses[1].zip", "user.addresses[2].zip"]
+  );
+  assert_eq!(
+    errors[0].path().segments(),
+    &[
+      PathSegment::Field("user".into()),
+      PathSegment::Field("addresses".into()),
+      PathSegment::Index(1),
+      PathSegment::Field("zip".into()),
+    ]
+  );
+  assert!(errors[0]
+    .to_string()
+    .starts_with("user.addresses[1].zip: guard failed: `zip.len() == 5` at "));
+}
+
+#[test]
+fn guard_nested_path() {
+  fn validate(ctx: &mut ValidationContext, age: u32) -> Result<(), GuardError> {
+    ctx.field("user", |ctx| {
+      ctx.field("age", |_| {
+        guard_report!(age >= 18);
+        Ok(())
+      })
+    })
+  }
+
+  let mut ctx = ValidationContext::new();
+  assert_eq!(validate(&mut ctx, 20), Ok(()));
+
+  let err = validate(&mut ctx, 12).unwrap_err();
+  assert_eq!(err.path().to_string(), "user.age");
+  assert!(ctx.path().is_empty());
+}
+
+#[test]
+fn guard_into_context() {
+  let mut ctx: ValidationContext = ValidationContext::new();
+
+  let r = ctx.field("items", |ctx| {
+    ctx.index(3, |_| -> Result<(), GuardError> {
+      guard_eq!(1 + 1, 3);
+      Ok(())
+    })
+  });
+
+  if let Err(err) = r {
+    ctx.fail(err);
+  }
+
+  let errors = ctx.finish().unwrap_err();
+  assert_eq!(errors[0].path().to_string(), "items[3]");
+}
+
+#[test]
+fn json_pointer_escape() {
+  let mut ctx: ValidationContext = ValidationContext::new();
+  ctx.field("a/b", |ctx| ctx.field("c~d", |ctx| check!(ctx, false)));
+
+  let errors = ctx.finish().unwrap_err();
+  assert_eq!(errors[0].path().to_json_pointer(), "/a~1b/c~0d");
+}
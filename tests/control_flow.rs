@@ -0,0 +1,128 @@
+use std::ops::ControlFlow;
+
+use try_guard::{guard, guard_break, guard_continue, guard_let, GuardFailed};
+
+#[test]
+fn continue_() {
+  let mut xs = Vec::new();
+
+  for x in 0..6 {
+    guard_continue!(x % 2 == 0);
+    xs.push(x);
+  }
+
+  assert_eq!(xs, [0, 2, 4]);
+}
+
+#[test]
+fn continue_label() {
+  let mut xs = Vec::new();
+
+  'outer: for x in 0..3 {
+    for y in 0..3 {
+      guard_continue!(y < x, 'outer);
+      xs.push((x, y));
+    }
+  }
+
+  assert_eq!(xs, [(1, 0), (2, 0), (2, 1)]);
+}
+
+#[test]
+fn break_() {
+  let mut xs = Vec::new();
+
+  for x in 0.. {
+    guard_break!(x < 3);
+    xs.push(x);
+  }
+
+  assert_eq!(xs, [0, 1, 2]);
+}
+
+#[test]
+fn break_value() {
+  let mut x = 1;
+
+  let y = loop {
+    guard_break!(x < 100, x);
+    x *= 3;
+  };
+
+  assert_eq!(y, 243);
+}
+
+#[test]
+fn break_label() {
+  let mut xs = Vec::new();
+
+  'outer: for x in 0..3 {
+    for y in 0..3 {
+      guard_break!(x + y < 3, 'outer);
+      xs.push((x, y));
+    }
+  }
+
+  assert_eq!(xs, [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)]);
+}
+
+#[test]
+fn break_label_value() {
+  fn sign(x: i32) -> &'static str {
+    'sign: {
+      guard_break!(x != 0, 'sign, "zero");
+      guard_break!(x > 0, 'sign, "negative");
+      "positive"
+    }
+  }
+
+  assert_eq!(sign(0), "zero");
+  assert_eq!(sign(-1), "negative");
+  assert_eq!(sign(1), "positive");
+}
+
+#[test]
+fn control_flow() {
+  fn foo(x: i32) -> ControlFlow<GuardFailed> {
+    guard!(x > 0);
+    ControlFlow::Continue(())
+  }
+
+  fn bar(x: Option<i32>) -> ControlFlow<&'static str, i32> {
+    guard_let!(Some(x) = x, "none");
+    ControlFlow::Continue(x)
+  }
+
+  assert_eq!(foo(1), ControlFlow::Continue(()));
+  assert_eq!(foo(0), ControlFlow::Break(GuardFailed));
+  assert_eq!(bar(Some(1)), ControlFlow::Continue(1));
+  assert_eq!(bar(None), ControlFlow::Break("none"));
+}
+
+#[test]
+fn control_flow_unit() {
+  fn foo(x: i32) -> ControlFlow<()> {
+    guard!(x > 0);
+    ControlFlow::Continue(())
+  }
+
+  fn bar(x: Option<i32>) -> ControlFlow<(), i32> {
+    guard_let!(Some(x) = x);
+    ControlFlow::Continue(x)
+  }
+
+  assert_eq!(foo(1), ControlFlow::Continue(()));
+  assert_eq!(foo(0), ControlFlow::Break(()));
+  assert_eq!(bar(Some(1)), ControlFlow::Continue(1));
+  assert_eq!(bar(None), ControlFlow::Break(()));
+}
+
+#[test]
+fn try_for_each() {
+  let flow = (1..10).try_for_each(|x| {
+    guard!(x < 5, x);
+    ControlFlow::Continue(())
+  });
+
+  assert_eq!(flow, ControlFlow::Break(5));
+}
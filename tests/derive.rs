@@ -0,0 +1,183 @@
+use try_guard::Validate;
+
+#[derive(Validate)]
+struct Address {
+  #[guard(self.len() == 5)]
+  zip: String,
+}
+
+#[derive(Validate)]
+struct User {
+  #[guard(!self.is_empty())]
+  name: String,
+  #[guard(range = 18..=130)]
+  age: u8,
+  #[guard(nested)]
+  address: Address,
+  #[guard(nested)]
+  #[guard(self.len() <= 2)]
+  others: Vec<Address>,
+}
+
+#[derive(Validate)]
+struct Port(#[guard(self > 1024)] u16);
+
+#[derive(Validate)]
+struct Wrapper<T>
+where
+  T: Validate,
+{
+  #[guard(nested)]
+  inner: Option<T>,
+}
+
+#[derive(Validate)]
+struct Pair<'a, T, U: 'a, const N: usize> {
+  #[guard(nested)]
+  first: Vec<T>,
+  #[guard(nested)]
+  second: &'a [U],
+  #[guard(self.len() == N)]
+  third: String,
+}
+
+fn user() -> User {
+  User {
+    name: "Alice".to_owned(),
+    age: 42,
+    address: Address {
+      zip: "12345".to_owned(),
+    },
+    others: Vec::new(),
+  }
+}
+
+#[test]
+fn valid() {
+  let user = user();
+  assert!(user.validate().is_ok());
+  assert!(user.validate_all().is_ok());
+}
+
+#[test]
+fn predicate() {
+  let user = User {
+    name: String::new(),
+    ..user()
+  };
+
+  let err = user.validate().unwrap_err();
+  assert_eq!(err.expr(), "!self.name.is_empty()");
+  assert_eq!(err.path().to_string(), "name");
+}
+
+#[test]
+fn range() {
+  let user = User { age: 12, ..user() };
+
+  let err = user.validate().unwrap_err();
+  assert_eq!(err.expr(), "(18..=130).contains(&self.age)");
+  assert_eq!(err.operands()[0].expr(), "self.age");
+  assert_eq!(err.operands()[0].value(), Some("12"));
+  assert_eq!(err.path().to_string(), "age");
+}
+
+#[test]
+fn nested() {
+  let user = User {
+    others: vec![
+      Address {
+        zip: "12345".to_owned(),
+      },
+      Address {
+        zip: "123".to_owned(),
+      },
+    ],
+    ..user()
+  };
+
+  let err = user.validate().unwrap_err();
+  assert_eq!(err.expr(), "self.zip.len() == 5");
+  assert_eq!(err.path().to_string(), "others[1].zip");
+}
+
+#[test]
+fn short_circuit() {
+  let user = User {
+    name: String::new(),
+    age: 0,
+    ..user()
+  };
+
+  assert_eq!(user.validate().unwrap_err().path().to_string(), "name");
+}
+
+#[test]
+fn accumulate() {
+  let user = User {
+    name: String::new(),
+    age: 0,
+    address: Address {
+      zip: "1".to_owned(),
+    },
+    others: vec![
+      Address {
+        zip: "2".to_owned(),
+      },
+      Address {
+        zip: "12345".to_owned(),
+      },
+      Address {
+        zip: "12345".to_owned(),
+      },
+    ],
+  };
+
+  let errors = user.validate_all().unwrap_err();
+  let paths = errors
+    .iter()
+    .map(|err| err.path().to_string())
+    .collect::<Vec<_>>();
+
+  assert_eq!(
+    paths,
+    ["name", "age", "address.zip", "others[0].zip", "others"]
+  );
+}
+
+#[test]
+fn tuple_struct() {
+  assert!(Port(8080).validate().is_ok());
+
+  let err = Port(80).validate().unwrap_err();
+  assert_eq!(err.expr(), "self.0 > 1024");
+  assert_eq!(err.path().to_string(), "0");
+}
+
+#[test]
+fn generic() {
+  let wrapper = Wrapper {
+    inner: Some(Port(80)),
+  };
+
+  assert_eq!(
+    wrapper.validate().unwrap_err().path().to_string(),
+    "inner.0"
+  );
+  assert!(Wrapper::<Port> { inner: None }.validate().is_ok());
+}
+
+#[test]
+fn generic_bounds() {
+  let ports = [Port(8080), Port(80)];
+  let pair = Pair::<_, _, 3> {
+    first: vec![Port(8080)],
+    second: &ports,
+    third: "abc".to_owned(),
+  };
+
+  assert_eq!(
+    pair.validate().unwrap_err().path().to_string(),
+    "second[1].0"
+  );
+}
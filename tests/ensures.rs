@@ -0,0 +1,125 @@
+use try_guard::{ensures, guard, requires, GuardFailed};
+
+#[ensures(ret.len() <= input.len())]
+fn trim(input: &str) -> Option<&str> {
+  Some(input.trim())
+}
+
+#[ensures(ret.len() < input.len())]
+fn broken_trim(input: &str) -> Option<&str> {
+  Some(input.trim())
+}
+
+#[ensures(*ret % 2 == 0)]
+fn double(x: i32) -> Result<i32, GuardFailed> {
+  if x == 0 {
+    return Ok(1);
+  }
+
+  guard!(x > 0);
+  Ok(x * 2)
+}
+
+struct Counter {
+  count: u32,
+}
+
+impl Counter {
+  #[ensures(self.count == old(self.count) + 1)]
+  fn incr(&mut self) -> Option<()> {
+    self.count += 1;
+    Some(())
+  }
+
+  #[ensures(self.count == old(self.count) + 1)]
+  fn incr_twice(&mut self) -> Option<()> {
+    self.count += 2;
+    Some(())
+  }
+
+  #[requires(self.count > 0, "empty")]
+  #[ensures(*ret == old(self.count), "not the old count")]
+  fn reset(&mut self) -> Result<u32, &'static str> {
+    let count = self.count;
+    self.count = 0;
+    Ok(count)
+  }
+}
+
+struct Buffer {
+  bytes: Vec<u8>,
+}
+
+impl Buffer {
+  #[ensures(**ret != 0)]
+  fn last_mut(&mut self) -> Option<&mut u8> {
+    self.bytes.last_mut()
+  }
+}
+
+#[ensures(*ret <= v.len() else { panic!("out of bounds") })]
+fn position(v: &[u8], x: u8) -> usize {
+  v.iter().position(|&y| y == x).unwrap_or(v.len() + 1)
+}
+
+#[ensures(*ret < 100)]
+fn apply<F: Fn(u8) -> u8>(f: F, x: u8) -> Option<u8> {
+  Some(f(x))
+}
+
+#[test]
+fn ret() {
+  assert_eq!(trim(" a "), Some("a"));
+  assert_eq!(broken_trim("a"), None);
+}
+
+#[test]
+fn early_return() {
+  assert_eq!(double(2), Ok(4));
+  // failures from the body are returned as is
+  assert_eq!(double(-2), Err(GuardFailed));
+  // early successes are still checked
+  assert_eq!(double(0), Err(GuardFailed));
+}
+
+#[test]
+fn old() {
+  let mut counter = Counter { count: 0 };
+  assert_eq!(counter.incr(), Some(()));
+  assert_eq!(counter.count, 1);
+  assert_eq!(counter.incr_twice(), None);
+}
+
+#[test]
+fn returned_borrow() {
+  let mut buffer = Buffer { bytes: vec![0, 1] };
+  *buffer.last_mut().unwrap() = 2;
+  assert_eq!(buffer.bytes, [0, 2]);
+
+  buffer.bytes.pop();
+  assert_eq!(buffer.last_mut(), None);
+}
+
+#[test]
+fn with_requires() {
+  let mut counter = Counter { count: 3 };
+  assert_eq!(counter.reset(), Ok(3));
+  assert_eq!(counter.reset(), Err("empty"));
+}
+
+#[test]
+fn else_block() {
+  assert_eq!(position(&[1, 2, 3], 2), 1);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn else_block_failure() {
+  position(&[1, 2, 3], 4);
+}
+
+#[test]
+fn fn_bound() {
+  assert_eq!(apply(|x| x + 1, 1), Some(2));
+  assert_eq!(apply(|x| x * 2, 60), None);
+}
@@ -0,0 +1,78 @@
+use try_guard::{verify, verify_or, verify_or_else, BoolExt, OptionExt, ResultExt};
+
+#[test]
+fn bool_guard() {
+  assert_eq!(true.guard(), Some(()));
+  assert_eq!(false.guard(), None);
+  assert_eq!(true.guard_then(|| 1), Some(1));
+  assert_eq!(false.guard_then(|| -> i32 { panic!("evaluated") }), None);
+  assert_eq!(true.guard_or("nope"), Ok(()));
+  assert_eq!(false.guard_or("nope"), Err("nope"));
+  assert_eq!(false.guard_or_else(|| "nope"), Err("nope"));
+}
+
+#[test]
+fn bool_guard_agrees_with_verify() {
+  for &cond in &[true, false] {
+    assert_eq!(verify!(cond), cond.guard());
+    assert_eq!(verify!(cond => 1), cond.guard_then(|| 1));
+    assert_eq!(verify_or!(cond, 0), cond.guard_or(0));
+    assert_eq!(verify_or_else!(cond, || 0), cond.guard_or_else(|| 0));
+  }
+}
+
+#[test]
+fn bool_guard_try() {
+  fn half(x: u32) -> Option<u32> {
+    (x % 2 == 0).guard()?;
+    Some(x / 2)
+  }
+
+  fn small(x: u32) -> Result<u32, &'static str> {
+    (x < 10).guard_or("too big")?;
+    Ok(x)
+  }
+
+  assert_eq!(half(4), Some(2));
+  assert_eq!(half(3), None);
+  assert_eq!(small(3), Ok(3));
+  assert_eq!(small(12), Err("too big"));
+}
+
+#[test]
+fn option_guard() {
+  assert_eq!(Some(2).guard(|x| *x > 1), Some(2));
+  assert_eq!(Some(0).guard(|x| *x > 1), None);
+  assert_eq!(None.guard(|x: &i32| *x > 1), None);
+
+  assert_eq!(Some(2).guard_or(|x| *x > 1, "nope"), Ok(2));
+  assert_eq!(Some(0).guard_or(|x| *x > 1, "nope"), Err("nope"));
+  assert_eq!(None.guard_or(|x: &i32| *x > 1, "nope"), Err("nope"));
+}
+
+#[test]
+fn result_ensure() {
+  let ok: Result<i32, String> = Ok(2);
+  let err: Result<i32, String> = Err("parse".to_owned());
+
+  assert_eq!(ok.clone().ensure(|x| *x > 1, "small".to_owned()), Ok(2));
+  assert_eq!(
+    ok.clone().ensure(|x| *x > 2, "small".to_owned()),
+    Err("small".to_owned())
+  );
+  assert_eq!(
+    err
+      .clone()
+      .ensure(|_| panic!("evaluated"), "small".to_owned()),
+    Err("parse".to_owned())
+  );
+
+  assert_eq!(
+    ok.ensure_else(|x| *x > 2, |x| format!("{} is small", x)),
+    Err("2 is small".to_owned())
+  );
+  assert_eq!(
+    err.ensure_else(|_| true, |_| unreachable!()),
+    Err("parse".to_owned())
+  );
+}
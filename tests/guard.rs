@@ -1,6 +1,4 @@
-#![cfg_attr(feature = "test-nightly", feature(try_trait), feature(try_blocks))]
-
-use try_guard::guard;
+use try_guard::{guard, GuardFailed};
 
 #[test]
 fn success() {
@@ -22,56 +20,141 @@ fn failure() {
   assert_eq!(foo(), None);
 }
 
-#[cfg(feature = "test-nightly")]
-mod nightly {
-  use super::*;
+#[test]
+fn result_success() {
+  fn foo() -> Result<i32, GuardFailed> {
+    guard!(1 < 2);
+    Ok(10)
+  }
+
+  assert_eq!(foo(), Ok(10));
+}
+
+#[test]
+fn result_failure() {
+  fn foo() -> Result<i32, GuardFailed> {
+    guard!(1 > 2);
+    Ok(10)
+  }
+
+  assert_eq!(foo(), Err(GuardFailed));
+}
+
+#[derive(Debug, PartialEq)]
+enum MyError {
+  TooSmall(i32),
+}
+
+#[test]
+fn result_custom_error_success() {
+  fn foo(x: i32) -> Result<i32, MyError> {
+    guard!(x > 2, MyError::TooSmall(x));
+    Ok(x)
+  }
+
+  assert_eq!(foo(3), Ok(3));
+}
+
+#[test]
+fn result_custom_error_failure() {
+  fn foo(x: i32) -> Result<i32, MyError> {
+    guard!(x > 2, MyError::TooSmall(x));
+    Ok(x)
+  }
+
+  assert_eq!(foo(1), Err(MyError::TooSmall(1)));
+}
+
+#[test]
+fn result_custom_error_into() {
+  fn foo(x: i32) -> Result<i32, String> {
+    guard!(x > 2, "too small");
+    Ok(x)
+  }
+
+  assert_eq!(foo(1), Err("too small".to_owned()));
+}
+
+#[test]
+fn custom_error_lazy() {
+  fn foo(x: i32, evaluated: &mut bool) -> Result<i32, String> {
+    guard!(x > 2, {
+      *evaluated = true;
+      "too small"
+    });
+    Ok(x)
+  }
 
-  #[test]
-  fn try_success() {
-    let foo: Option<i32> = try {
-      guard!(1 < 2);
-      10
-    };
+  let mut evaluated = false;
+  assert_eq!(foo(3, &mut evaluated), Ok(3));
+  assert!(!evaluated);
+}
 
-    assert_eq!(foo, Some(10));
+#[test]
+fn else_return() {
+  fn foo(x: i32) -> Result<i32, String> {
+    guard!(x > 2 else { return Ok(0) });
+    Ok(x)
   }
 
-  #[test]
-  fn try_failure() {
-    let foo: Option<i32> = try {
-      guard!(1 > 2);
-      10
-    };
+  assert_eq!(foo(3), Ok(3));
+  assert_eq!(foo(1), Ok(0));
+}
+
+#[test]
+fn else_continue() {
+  let mut kept = Vec::new();
 
-    assert_eq!(foo, None);
+  for x in 0..6 {
+    guard!(x % 2 == 0 else { continue });
+    kept.push(x);
   }
 
-  #[derive(Debug, PartialEq)]
-  struct CustomError;
+  assert_eq!(kept, vec![0, 2, 4]);
+}
+
+#[test]
+fn else_break_label() {
+  let mut visited = 0;
 
-  impl From<std::option::NoneError> for CustomError {
-    fn from(_: std::option::NoneError) -> Self {
-      CustomError
+  'outer: for i in 0..3 {
+    for j in 0..3 {
+      guard!(i + j < 3 else { break 'outer });
+      visited += 1;
     }
   }
 
-  #[test]
-  fn try_result_success() {
-    let foo: Result<i32, CustomError> = try {
-      guard!(1 < 2);
-      10
-    };
+  assert_eq!(visited, 5);
+}
 
-    assert_eq!(foo, Ok(10));
+#[test]
+fn else_if_predicate() {
+  fn foo(x: i32) -> i32 {
+    guard!(if x > 0 { x < 10 } else { false } else { return -1 });
+    x
   }
 
-  #[test]
-  fn try_result_failure() {
-    let foo: Result<i32, CustomError> = try {
-      guard!(1 > 2);
-      10
-    };
+  assert_eq!(foo(5), 5);
+  assert_eq!(foo(15), -1);
+  assert_eq!(foo(-5), -1);
+}
+
+#[test]
+fn predicate() {
+  use try_guard::predicates::IsPositive;
 
-    assert_eq!(foo, Err(CustomError));
+  fn foo(x: i32) -> Option<i32> {
+    guard!(x => IsPositive);
+    Some(x)
   }
+
+  fn bar(x: i32) -> Result<i32, &'static str> {
+    guard!(x => |x: &i32| x % 2 == 0, "odd");
+    Ok(x)
+  }
+
+  assert_eq!(foo(1), Some(1));
+  assert_eq!(foo(0), None);
+  assert_eq!(bar(2), Ok(2));
+  assert_eq!(bar(3), Err("odd"));
 }
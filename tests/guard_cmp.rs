@@ -0,0 +1,116 @@
+use try_guard::{guard_eq, guard_ge, guard_gt, guard_le, guard_lt, guard_ne, GuardError};
+
+#[test]
+fn eq_success() {
+  fn foo(a: u32, b: u32) -> Result<(), GuardError> {
+    guard_eq!(a, b);
+    Ok(())
+  }
+
+  assert_eq!(foo(1, 1), Ok(()));
+}
+
+#[test]
+fn eq_failure() {
+  fn foo(a: &[u32], b: usize) -> Result<(), GuardError> {
+    guard_eq!(a.len(), b);
+    Ok(())
+  }
+
+  let err = foo(&[1, 2], 3).unwrap_err();
+  assert_eq!(err.expr(), "a.len() == b");
+  assert_eq!(err.operator(), Some("=="));
+  assert_eq!(err.operands().len(), 2);
+  assert_eq!(err.operands()[0].expr(), "a.len()");
+  assert_eq!(err.operands()[0].value(), Some("2"));
+  assert_eq!(err.operands()[1].expr(), "b");
+  assert_eq!(err.operands()[1].value(), Some("3"));
+}
+
+type Guard = fn(i32, i32) -> Result<(), GuardError>;
+
+#[test]
+fn all_operators() {
+  fn check(a: i32, b: i32) -> Vec<Option<&'static str>> {
+    let guards: Vec<Guard> = vec![
+      |a, b| {
+        guard_eq!(a, b);
+        Ok(())
+      },
+      |a, b| {
+        guard_ne!(a, b);
+        Ok(())
+      },
+      |a, b| {
+        guard_lt!(a, b);
+        Ok(())
+      },
+      |a, b| {
+        guard_le!(a, b);
+        Ok(())
+      },
+      |a, b| {
+        guard_gt!(a, b);
+        Ok(())
+      },
+      |a, b| {
+        guard_ge!(a, b);
+        Ok(())
+      },
+    ];
+
+    guards
+      .into_iter()
+      .map(|guard| guard(a, b).err().and_then(|err| err.operator()))
+      .collect()
+  }
+
+  assert_eq!(
+    check(1, 2),
+    vec![Some("=="), None, None, None, Some(">"), Some(">=")]
+  );
+  assert_eq!(
+    check(2, 2),
+    vec![None, Some("!="), Some("<"), None, Some(">"), None]
+  );
+}
+
+#[test]
+fn evaluated_once() {
+  fn foo(calls: &mut u32) -> Result<(), GuardError> {
+    guard_lt!(
+      {
+        *calls += 1;
+        *calls
+      },
+      1
+    );
+    Ok(())
+  }
+
+  let mut calls = 0;
+  assert!(foo(&mut calls).is_err());
+  assert_eq!(calls, 1);
+}
+
+#[test]
+fn custom_error() {
+  fn foo(a: u32, b: u32) -> Result<(), String> {
+    guard_ge!(a, b, format!("{} < {}", a, b));
+    Ok(())
+  }
+
+  assert_eq!(foo(2, 1), Ok(()));
+  assert_eq!(foo(1, 2), Err("1 < 2".to_owned()));
+}
+
+#[test]
+fn option() {
+  fn foo(a: &str, b: &str) -> Option<()> {
+    guard_ne!(a, b);
+    Some(())
+  }
+
+  assert_eq!(foo("a", "b"), Some(()));
+  assert_eq!(foo("a", "a"), None);
+}
@@ -0,0 +1,100 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use try_guard::{guard, guard_let, guard_report, GuardEmpty};
+
+#[test]
+fn collections() {
+  fn vec(cond: bool) -> Vec<i32> {
+    guard!(cond);
+    vec![1]
+  }
+
+  fn string(cond: bool) -> String {
+    guard!(cond, "ignored");
+    "a".to_owned()
+  }
+
+  fn vec_deque(cond: bool) -> VecDeque<i32> {
+    guard!(cond);
+    vec![1].into()
+  }
+
+  fn hash_map(cond: bool) -> HashMap<i32, i32> {
+    guard!(cond);
+    vec![(1, 1)].into_iter().collect()
+  }
+
+  fn hash_set(cond: bool) -> HashSet<i32> {
+    guard_report!(cond);
+    vec![1].into_iter().collect()
+  }
+
+  fn btree_map(x: Option<i32>) -> BTreeMap<i32, i32> {
+    guard_let!(Some(x) = x);
+    vec![(x, x)].into_iter().collect()
+  }
+
+  fn btree_set(cond: bool) -> BTreeSet<i32> {
+    guard!(cond);
+    vec![1].into_iter().collect()
+  }
+
+  assert_eq!(vec(true), [1]);
+  assert!(vec(false).is_empty());
+  assert_eq!(string(true), "a");
+  assert!(string(false).is_empty());
+  assert_eq!(vec_deque(true).len(), 1);
+  assert!(vec_deque(false).is_empty());
+  assert_eq!(hash_map(true).len(), 1);
+  assert!(hash_map(false).is_empty());
+  assert_eq!(hash_set(true).len(), 1);
+  assert!(hash_set(false).is_empty());
+  assert_eq!(btree_map(Some(1)).len(), 1);
+  assert!(btree_map(None).is_empty());
+  assert_eq!(btree_set(true).len(), 1);
+  assert!(btree_set(false).is_empty());
+}
+
+#[test]
+fn unit() {
+  fn foo(cond: bool, calls: &mut u32) {
+    guard!(cond);
+    *calls += 1;
+  }
+
+  let mut calls = 0;
+  foo(false, &mut calls);
+  foo(true, &mut calls);
+  assert_eq!(calls, 1);
+}
+
+#[test]
+fn bool() {
+  fn foo(x: u32) -> bool {
+    guard!(x > 0);
+    true
+  }
+
+  assert!(foo(1));
+  assert!(!foo(0));
+}
+
+#[test]
+fn custom() {
+  #[derive(Debug, PartialEq)]
+  struct Score(u32);
+
+  impl GuardEmpty for Score {
+    fn empty() -> Self {
+      Score(0)
+    }
+  }
+
+  fn score(hits: u32) -> Score {
+    guard!(hits <= 10);
+    Score(hits * 10)
+  }
+
+  assert_eq!(score(3), Score(30));
+  assert_eq!(score(11), Score(0));
+}
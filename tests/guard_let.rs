@@ -0,0 +1,59 @@
+use try_guard::{guard_let, GuardFailed};
+
+#[test]
+fn option_success() {
+  fn foo(x: Option<i32>) -> Option<i32> {
+    guard_let!(Some(y) = x);
+    Some(y + 1)
+  }
+
+  assert_eq!(foo(Some(1)), Some(2));
+}
+
+#[test]
+fn option_failure() {
+  fn foo(x: Option<i32>) -> Option<i32> {
+    guard_let!(Some(y) = x);
+    Some(y + 1)
+  }
+
+  assert_eq!(foo(None), None);
+}
+
+#[test]
+fn result() {
+  fn foo(x: Option<i32>) -> Result<i32, GuardFailed> {
+    guard_let!(Some(y) = x);
+    Ok(y + 1)
+  }
+
+  assert_eq!(foo(Some(1)), Ok(2));
+  assert_eq!(foo(None), Err(GuardFailed));
+}
+
+#[test]
+fn custom_error() {
+  fn foo(x: Result<i32, String>) -> Result<i32, String> {
+    guard_let!(Ok(y) = x, "not ok");
+    Ok(y + 1)
+  }
+
+  assert_eq!(foo(Ok(1)), Ok(2));
+  assert_eq!(foo(Err("nope".to_owned())), Err("not ok".to_owned()));
+}
+
+#[test]
+fn struct_pattern() {
+  struct Point {
+    x: i32,
+    y: Option<i32>,
+  }
+
+  fn foo(p: Point) -> Option<i32> {
+    guard_let!(Point { x, y: Some(y) } = p);
+    Some(x + y)
+  }
+
+  assert_eq!(foo(Point { x: 1, y: Some(2) }), Some(3));
+  assert_eq!(foo(Point { x: 1, y: None }), None);
+}
@@ -0,0 +1,302 @@
+use try_guard::{guard_report, GuardError};
+
+#[test]
+fn success() {
+  fn foo(x: u32) -> Result<u32, GuardError> {
+    guard_report!(x > 2);
+    Ok(x)
+  }
+
+  assert_eq!(foo(3), Ok(3));
+}
+
+#[test]
+fn failure() {
+  fn foo(x: u32) -> Result<u32, GuardError> {
+    guard_report!(x > 2);
+    Ok(x)
+  }
+
+  let err = foo(1).unwrap_err();
+  assert_eq!(err.expr(), "x > 2");
+  assert_eq!(err.file(), file!());
+  assert_eq!(err.line(), 16);
+  assert_eq!(err.column(), 5);
+  assert_eq!(err.module_path(), Some(module_path!()));
+  assert_eq!(
+    err.to_string(),
+    format!(
+      "guard failed: `x > 2` at {}:16:5\n  `x` = 1\n  `2` = 2",
+      file!()
+    )
+  );
+}
+
+#[test]
+fn option() {
+  fn foo(x: u32) -> Option<u32> {
+    guard_report!(x > 2);
+    Some(x)
+  }
+
+  assert_eq!(foo(1), None);
+}
+
+#[derive(Debug)]
+enum ApiError {
+  Guard(GuardError),
+}
+
+impl From<GuardError> for ApiError {
+  fn from(err: GuardError) -> Self {
+    ApiError::Guard(err)
+  }
+}
+
+#[test]
+fn into_custom_error() {
+  fn foo(admin: bool) -> Result<(), ApiError> {
+    guard_report!(admin);
+    Ok(())
+  }
+
+  match foo(false) {
+    Err(ApiError::Guard(err)) => assert_eq!(err.expr(), "admin"),
+    Ok(()) => panic!("guard should have failed"),
+  }
+}
+
+#[test]
+fn comparison_operands() {
+  fn foo(a: &[u32], limit: usize) -> Result<(), GuardError> {
+    guard_report!(a.len() < limit);
+    Ok(())
+  }
+
+  assert_eq!(foo(&[1], 2), Ok(()));
+
+  let err = foo(&[1, 2, 3], 2).unwrap_err();
+  assert_eq!(err.expr(), "a.len() < limit");
+
+  let operands = err.operands();
+  assert_eq!(operands.len(), 2);
+  assert_eq!(operands[0].expr(), "a.len()");
+  assert_eq!(operands[0].value(), Some("3"));
+  assert_eq!(operands[1].expr(), "limit");
+  assert_eq!(operands[1].value(), Some("2"));
+  assert!(err.to_string().ends_with("\n  `a.len()` = 3\n  `limit` = 2"));
+}
+
+#[test]
+fn comparison_evaluated_once() {
+  fn foo(calls: &mut u32) -> Result<(), GuardError> {
+    guard_report!({ *calls += 1; *calls } == 2);
+    Ok(())
+  }
+
+  let mut calls = 0;
+  assert!(foo(&mut calls).is_err());
+  assert_eq!(calls, 1);
+}
+
+#[test]
+fn comparison_all_operators() {
+  fn foo(a: i32, b: i32) -> Vec<bool> {
+    fn check(r: Result<(), GuardError>) -> bool {
+      let ok = r.is_ok();
+      if let Err(err) = r {
+        assert_eq!(err.operands().len(), 2);
+      }
+      ok
+    }
+
+    vec![
+      check((|| { guard_report!(a == b); Ok(()) })()),
+      check((|| { guard_report!(a != b); Ok(()) })()),
+      check((|| { guard_report!(a < b); Ok(()) })()),
+      check((|| { guard_report!(a <= b); Ok(()) })()),
+      check((|| { guard_report!(a > b); Ok(()) })()),
+      check((|| { guard_report!(a >= b); Ok(()) })()),
+    ]
+  }
+
+  assert_eq!(foo(1, 2), vec![false, true, true, true, false, false]);
+  assert_eq!(foo(2, 2), vec![true, false, false, true, false, true]);
+}
+
+#[test]
+fn non_debug_operand() {
+  #[derive(PartialEq)]
+  struct Opaque(u32);
+
+  fn foo(x: Opaque) -> Result<(), GuardError> {
+    guard_report!(x == Opaque(3));
+    Ok(())
+  }
+
+  let err = foo(Opaque(1)).unwrap_err();
+  assert_eq!(err.operands()[0].expr(), "x");
+  assert_eq!(err.operands()[0].value(), None);
+  assert!(err.to_string().ends_with("\n  `x`\n  `Opaque(3)`"));
+}
+
+#[test]
+fn method_call_receiver() {
+  struct Team {
+    members: Vec<&'static str>,
+  }
+
+  impl Team {
+    fn check(&self, name: &str) -> Result<(), GuardError> {
+      guard_report!(self.members.contains(&name));
+      Ok(())
+    }
+  }
+
+  let team = Team {
+    members: vec!["alice", "bob"],
+  };
+
+  assert_eq!(team.check("bob"), Ok(()));
+
+  let err = team.check("carol").unwrap_err();
+  assert_eq!(err.operands().len(), 1);
+  assert_eq!(err.operands()[0].expr(), "self.members");
+  assert_eq!(err.operands()[0].value(), Some(r#"["alice", "bob"]"#));
+}
+
+#[test]
+fn method_call_receiver_consumed() {
+  fn foo(name: Option<String>) -> Result<(), GuardError> {
+    guard_report!(name.map_or(false, |name| !name.is_empty()));
+    Ok(())
+  }
+
+  fn bar(a: Vec<u8>, b: Vec<u8>) -> Result<(), GuardError> {
+    guard_report!(a.into_iter().eq(b));
+    Ok(())
+  }
+
+  assert_eq!(foo(Some("alice".to_owned())), Ok(()));
+  assert!(foo(Some(String::new())).unwrap_err().operands().is_empty());
+  assert!(bar(vec![1], vec![2]).unwrap_err().operands().is_empty());
+}
+
+#[test]
+fn method_call_receiver_not_captured_on_success() {
+  use std::cell::Cell;
+  use std::fmt;
+
+  struct Counted<'a>(&'a Cell<u32>);
+
+  impl Counted<'_> {
+    fn ok(&self) -> bool {
+      true
+    }
+  }
+
+  impl fmt::Debug for Counted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      self.0.set(self.0.get() + 1);
+      f.write_str("Counted")
+    }
+  }
+
+  fn foo(c: &Counted) -> Result<(), GuardError> {
+    guard_report!(c.ok());
+    Ok(())
+  }
+
+  let calls = Cell::new(0);
+
+  for _ in 0..100 {
+    assert_eq!(foo(&Counted(&calls)), Ok(()));
+  }
+
+  assert_eq!(calls.get(), 0);
+}
+
+#[test]
+fn method_call_receiver_mutated() {
+  #[derive(Debug)]
+  struct Tokens(u32);
+
+  impl Tokens {
+    fn take(&mut self) -> bool {
+      self.0 = self.0.saturating_sub(1);
+      self.0 > 0
+    }
+  }
+
+  fn foo(tokens: &mut Tokens) -> Result<(), GuardError> {
+    guard_report!(tokens.take());
+    Ok(())
+  }
+
+  let err = foo(&mut Tokens(1)).unwrap_err();
+  assert_eq!(err.operands()[0].value(), Some("Tokens(0)"));
+}
+
+#[test]
+fn not_decomposed() {
+  fn foo(a: u32, b: u32) -> Result<(), GuardError> {
+    guard_report!(a < 3 && b < 3);
+    Ok(())
+  }
+
+  fn bar(xs: &[u32]) -> Result<(), GuardError> {
+    guard_report!(xs.iter().any(|x| *x > 2));
+    Ok(())
+  }
+
+  fn baz(a: u32) -> Result<(), GuardError> {
+    guard_report!(a as u64 == Vec::<u64>::new().len() as u64);
+    Ok(())
+  }
+
+  fn qux(name: Option<String>) -> Result<(), GuardError> {
+    guard_report!((name.map_or(false, |name| !name.is_empty())));
+    Ok(())
+  }
+
+  assert!(foo(1, 4).unwrap_err().operands().is_empty());
+  assert!(bar(&[1, 2]).unwrap_err().operands().is_empty());
+  assert!(baz(1).unwrap_err().operands().is_empty());
+  assert!(qux(None).unwrap_err().operands().is_empty());
+}
+
+#[test]
+fn predicate() {
+  use try_guard::predicates::{InBounds, IsNonEmpty};
+  use try_guard::Predicate;
+
+  fn foo(name: &str) -> Result<(), GuardError> {
+    guard_report!(name => IsNonEmpty.and(|name: &&str| name.is_ascii()));
+    Ok(())
+  }
+
+  fn bar(x: i32) -> Result<(), GuardError> {
+    guard_report!(x => InBounds::<0, 10>.or(InBounds::<20, 30>).not());
+    Ok(())
+  }
+
+  assert_eq!(foo("alice"), Ok(()));
+
+  let err = foo("").unwrap_err();
+  assert_eq!(err.expr(), "name => IsNonEmpty.and(|name: &&str| name.is_ascii())");
+  assert_eq!(err.reason(), Some("non-empty"));
+  assert_eq!(foo("élise").unwrap_err().reason(), Some("custom predicate"));
+
+  assert_eq!(bar(15), Ok(()));
+
+  let err = bar(25).unwrap_err();
+  assert_eq!(err.reason(), Some("not (between 0 and 10 or between 20 and 30)"));
+  assert_eq!(
+    err.to_string(),
+    format!(
+      "guard failed: `x => InBounds::<0, 10>.or(InBounds::<20, 30>).not()` at {}:{}:5\n  expected not (between 0 and 10 or between 20 and 30)\n  `x` = 25",
+      file!(),
+      err.line(),
+    )
+  );
+}
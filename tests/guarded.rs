@@ -0,0 +1,182 @@
+use try_guard::{guard, guard_eq, guard_let, guard_report, guarded, GuardError, GuardFailed};
+
+/// Define a `#[guarded]` function, and `$docs` as the documentation lines `#[guarded]` appends to
+/// it.
+macro_rules! guarded_fn {
+  ($docs:ident, $($item:tt)+) => {
+    #[guarded]
+    $($item)+
+
+    const $docs: &[&str] = try_guard_derive::__guarded_docs!($($item)+);
+  };
+}
+
+#[derive(Debug, PartialEq)]
+enum Error {
+  Empty,
+  Failed(GuardError),
+}
+
+impl From<GuardError> for Error {
+  fn from(err: GuardError) -> Self {
+    Error::Failed(err)
+  }
+}
+
+guarded_fn! {
+  SUM_DOCS,
+  /// Sum the parsed numbers.
+  fn sum(input: &str, max: usize) -> Result<u32, Error> {
+    guard!(!input.is_empty(), Error::Empty);
+
+    let mut sum = 0;
+
+    for n in input.split(',') {
+      guard_report!(n.len() <= max);
+      sum += n.parse::<u32>().unwrap_or(0);
+    }
+
+    guard_eq!(sum % 2, 0);
+    Ok(sum)
+  }
+}
+
+guarded_fn! {
+  FIRST_DOCS,
+  fn first(v: &[u8]) -> Option<u8> {
+    guard_let!(Some(&x) = v.first());
+    guard!(x > 0);
+    Some(x)
+  }
+}
+
+guarded_fn! {
+  CLAMPED_DOCS,
+  fn clamped(x: i32) -> i32 {
+    guard!(x >= 0 else { return 0 });
+    x
+  }
+}
+
+guarded_fn! {
+  UNGUARDED_DOCS,
+  fn unguarded() -> Result<(), GuardFailed> {
+    Ok(())
+  }
+}
+
+guarded_fn! {
+  NESTED_DOCS,
+  fn nested(xs: &[i32]) -> Option<Vec<i32>> {
+    fn positive(x: i32) -> Option<i32> {
+      guard!(x > 0);
+      Some(x)
+    }
+
+    guard!(xs.len() < 4 || xs.is_empty());
+    let doubled = xs.iter().map(|x| -> Option<i32> {
+      guard!(*x < 100);
+      Some(x * 2)
+    });
+    let even = |x: i32| guard!(x % 2 == 0);
+    even(3);
+
+    doubled.map(|x| positive(x?)).collect()
+  }
+}
+
+guarded_fn! {
+  APPLY_DOCS,
+  fn apply<F: Fn(u8) -> u8>(f: F, x: u8) -> Option<u8> {
+    guard!(x < 100);
+    Some(f(x))
+  }
+}
+
+#[test]
+fn behavior_is_unchanged() {
+  assert_eq!(sum("1,3", 2), Ok(4));
+  assert_eq!(sum("", 2), Err(Error::Empty));
+  assert!(matches!(sum("100", 2), Err(Error::Failed(_))));
+  assert!(matches!(sum("1", 2), Err(Error::Failed(_))));
+
+  assert_eq!(first(&[3]), Some(3));
+  assert_eq!(first(&[]), None);
+  assert_eq!(first(&[0]), None);
+
+  assert_eq!(clamped(3), 3);
+  assert_eq!(clamped(-3), 0);
+
+  assert_eq!(unguarded(), Ok(()));
+
+  assert_eq!(nested(&[1, 2]), Some(vec![2, 4]));
+  assert_eq!(nested(&[-1]), None);
+
+  assert_eq!(apply(|x| x + 1, 1), Some(2));
+  assert_eq!(apply(|x| x + 1, 100), None);
+}
+
+#[test]
+fn errors() {
+  assert_eq!(
+    SUM_DOCS,
+    [
+      "",
+      "# Errors",
+      "",
+      "  - `Error::Empty` unless `!input.is_empty()`.",
+      "  - `GuardError` unless `n.len() <= max`.",
+      "  - `GuardError` unless `sum % 2 == 0`.",
+    ]
+  );
+}
+
+#[test]
+fn preconditions() {
+  assert_eq!(
+    FIRST_DOCS,
+    [
+      "",
+      "# Preconditions",
+      "",
+      "Returns `None` unless:",
+      "",
+      "  - `v.first()` matches `Some(&x)`",
+      "  - `x > 0`",
+    ]
+  );
+
+  assert_eq!(CLAMPED_DOCS, ["", "# Preconditions", "", "  - `x >= 0`"]);
+
+  assert_eq!(
+    APPLY_DOCS,
+    [
+      "",
+      "# Preconditions",
+      "",
+      "Returns `None` unless:",
+      "",
+      "  - `x < 100`",
+    ]
+  );
+}
+
+#[test]
+fn no_guard() {
+  assert!(UNGUARDED_DOCS.is_empty());
+}
+
+#[test]
+fn nested_functions_and_closures() {
+  assert_eq!(
+    NESTED_DOCS,
+    [
+      "",
+      "# Preconditions",
+      "",
+      "Returns `None` unless:",
+      "",
+      "  - `xs.len() < 4 || xs.is_empty()`",
+    ]
+  );
+}
@@ -0,0 +1,170 @@
+use std::sync::Mutex;
+use try_guard::{invariant, set_invariant_log_hook, GuardError};
+
+#[derive(Debug)]
+struct Sorted {
+  items: Vec<i32>,
+}
+
+#[invariant(self.items.windows(2).all(|w| w[0] <= w[1]))]
+impl Sorted {
+  fn new() -> Self {
+    Sorted { items: Vec::new() }
+  }
+
+  fn insert(&mut self, x: i32) {
+    let i = self.items.partition_point(|&y| y < x);
+    self.items.insert(i, x);
+  }
+
+  fn push_unchecked(&mut self, x: i32) {
+    self.items.push(x);
+  }
+
+  fn try_push(&mut self, x: i32) -> Result<(), GuardError> {
+    self.items.push(x);
+    Ok(())
+  }
+
+  fn try_push_opt(&mut self, x: i32) -> Option<usize> {
+    if x == 0 {
+      return None;
+    }
+
+    self.items.push(x);
+    Some(self.items.len())
+  }
+
+  fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  fn last_mut(&mut self) -> Option<&mut i32> {
+    self.items.last_mut()
+  }
+
+  fn iter_mut(&mut self) -> std::slice::IterMut<'_, i32> {
+    self.items.iter_mut()
+  }
+
+  fn map<F: FnMut(i32) -> i32>(&mut self, f: F) {
+    self.items = self.items.iter().copied().map(f).collect();
+  }
+
+  fn try_map<F: FnMut(i32) -> i32>(&mut self, f: F) -> Option<usize> {
+    self.items = self.items.iter().copied().map(f).collect();
+    Some(self.items.len())
+  }
+
+  #[invariant(skip)]
+  fn reverse(&mut self) {
+    self.items.reverse();
+  }
+}
+
+struct Account {
+  balance: i64,
+  overdrafts: u32,
+}
+
+#[invariant(self.balance >= 0, policy = log)]
+#[invariant(self.overdrafts < 2)]
+impl Account {
+  fn withdraw(&mut self, amount: i64) {
+    self.balance -= amount;
+
+    if self.balance < 0 {
+      self.overdrafts += 1;
+    }
+  }
+}
+
+#[test]
+fn preserved() {
+  let mut sorted = Sorted::new();
+  sorted.insert(3);
+  sorted.insert(1);
+  sorted.insert(2);
+  assert_eq!(sorted.items, [1, 2, 3]);
+  assert_eq!(sorted.len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "invariant violated")]
+fn panic_policy() {
+  let mut sorted = Sorted { items: vec![1, 2] };
+  sorted.push_unchecked(0);
+}
+
+#[test]
+#[should_panic(expected = "invariant violated")]
+fn fn_bound() {
+  let mut sorted = Sorted { items: vec![1, 2] };
+  sorted.map(|x| x * 2);
+  assert_eq!(sorted.items, [2, 4]);
+  sorted.map(|x| -x);
+}
+
+#[test]
+fn fn_bound_option() {
+  let mut sorted = Sorted { items: vec![1, 2] };
+  assert_eq!(sorted.try_map(|x| x + 1), Some(2));
+  assert_eq!(sorted.try_map(|x| -x), None);
+}
+
+#[test]
+fn result() {
+  let mut sorted = Sorted { items: vec![1, 2] };
+  assert!(sorted.try_push(3).is_ok());
+
+  let err = sorted.try_push(0).unwrap_err();
+  assert_eq!(err.expr(), "self.items.windows(2).all(|w| w[0] <= w[1])");
+}
+
+#[test]
+fn option() {
+  let mut sorted = Sorted { items: vec![1, 2] };
+  assert_eq!(sorted.try_push_opt(3), Some(3));
+  assert_eq!(sorted.try_push_opt(0), None);
+  assert_eq!(sorted.try_push_opt(-1), None);
+}
+
+#[test]
+fn log_policy() {
+  static LOGGED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+  set_invariant_log_hook(|error| LOGGED.lock().unwrap().push(error.expr().to_owned()));
+
+  let mut account = Account {
+    balance: 10,
+    overdrafts: 0,
+  };
+
+  account.withdraw(20);
+  assert_eq!(account.balance, -10);
+  // other tests may log too
+  assert!(LOGGED.lock().unwrap().iter().any(|expr| expr == "self.balance >= 0"));
+}
+
+#[test]
+fn skipped() {
+  let mut sorted = Sorted {
+    items: vec![1, 2, 3],
+  };
+
+  *sorted.last_mut().unwrap() = 0;
+  sorted.iter_mut().for_each(|x| *x = -*x);
+  sorted.reverse();
+  assert_eq!(sorted.items, [0, -2, -1]);
+}
+
+#[test]
+#[should_panic(expected = "self.overdrafts < 2")]
+fn stacked() {
+  let mut account = Account {
+    balance: 0,
+    overdrafts: 1,
+  };
+
+  account.withdraw(20);
+}
@@ -0,0 +1,115 @@
+#![feature(try_blocks_heterogeneous, try_trait_v2)]
+
+use std::ops::{ControlFlow, FromResidual};
+use try_guard::{guard, guard_try, GuardFailed, GuardResidual, GuardTarget};
+
+#[test]
+fn try_success() {
+  let foo = try bikeshed Option<i32> {
+    guard_try!(1 < 2);
+    10
+  };
+
+  assert_eq!(foo, Some(10));
+}
+
+#[test]
+fn try_failure() {
+  let foo = try bikeshed Option<i32> {
+    guard_try!(1 > 2);
+    10
+  };
+
+  assert_eq!(foo, None);
+}
+
+#[derive(Debug, PartialEq)]
+struct CustomError;
+
+impl From<GuardFailed> for CustomError {
+  fn from(_: GuardFailed) -> Self {
+    CustomError
+  }
+}
+
+#[test]
+fn try_result_success() {
+  let foo = try bikeshed Result<i32, CustomError> {
+    guard_try!(1 < 2);
+    10
+  };
+
+  assert_eq!(foo, Ok(10));
+}
+
+#[test]
+fn try_result_failure() {
+  let foo = try bikeshed Result<i32, CustomError> {
+    guard_try!(1 > 2);
+    10
+  };
+
+  assert_eq!(foo, Err(CustomError));
+}
+
+#[test]
+fn try_custom_error_failure() {
+  let foo = try bikeshed Result<i32, String> {
+    guard_try!(1 > 2, "nope");
+    10
+  };
+
+  assert_eq!(foo, Err("nope".to_owned()));
+}
+
+#[test]
+fn try_control_flow_unit() {
+  let foo = try bikeshed ControlFlow<(), i32> {
+    guard_try!(1 > 2);
+    10
+  };
+
+  assert_eq!(foo, ControlFlow::Break(()));
+}
+
+#[derive(Debug, PartialEq)]
+enum MyGuard<T> {
+  Just(T),
+  Nothing,
+}
+
+impl<T> FromResidual<GuardResidual> for MyGuard<T> {
+  fn from_residual(_: GuardResidual) -> Self {
+    MyGuard::Nothing
+  }
+}
+
+#[test]
+fn custom_residual() {
+  fn foo(cond: bool) -> MyGuard<i32> {
+    guard_try!(cond);
+    MyGuard::Just(10)
+  }
+
+  assert_eq!(foo(true), MyGuard::Just(10));
+  assert_eq!(foo(false), MyGuard::Nothing);
+}
+
+#[derive(Debug, PartialEq)]
+struct Target;
+
+impl GuardTarget for Target {
+  fn guard_failed(_: GuardFailed) -> Self {
+    Target
+  }
+}
+
+#[test]
+fn guard_target_unaffected() {
+  fn foo() -> Target {
+    guard!(1 > 2);
+    unreachable!()
+  }
+
+  assert_eq!(foo(), Target);
+}
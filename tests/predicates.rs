@@ -0,0 +1,154 @@
+use try_guard::predicates::{InBounds, IsNonEmpty, IsPositive};
+use try_guard::Predicate;
+
+#[test]
+fn describe() {
+  assert_eq!(Predicate::<str>::describe(&IsNonEmpty), "non-empty");
+  assert_eq!(Predicate::<i32>::describe(&IsPositive), "positive");
+  assert_eq!(
+    Predicate::<i32>::describe(&InBounds::<1, 3>),
+    "between 1 and 3"
+  );
+}
+
+#[test]
+fn and() {
+  let p = IsPositive.and(InBounds::<0, 10>);
+
+  assert!(p.test(&5));
+  assert_eq!(p.explain(&5), None);
+  assert_eq!(p.explain(&0), Some("positive".to_owned()));
+  assert_eq!(p.explain(&11), Some("between 0 and 10".to_owned()));
+  assert_eq!(p.describe(), "positive and between 0 and 10");
+}
+
+#[test]
+fn or() {
+  let p = InBounds::<0, 10>.or(InBounds::<20, 30>);
+
+  assert!(p.test(&5));
+  assert!(p.test(&25));
+  assert_eq!(p.explain(&25), None);
+  assert_eq!(
+    p.explain(&15),
+    Some("between 0 and 10 or between 20 and 30".to_owned())
+  );
+}
+
+#[test]
+fn not() {
+  let p = IsPositive.not();
+
+  assert!(p.test(&-1));
+  assert!(!p.test(&1));
+  assert_eq!(p.explain(&1), Some("not (positive)".to_owned()));
+}
+
+#[test]
+fn map() {
+  let p = InBounds::<1, 3>.map(|s: &str| s.len());
+
+  assert!(p.test("ab"));
+  assert_eq!(p.explain(""), Some("between 1 and 3".to_owned()));
+}
+
+#[test]
+fn closure() {
+  let p = |x: &u32| x % 2 == 0;
+
+  assert!(p.test(&2));
+  assert_eq!(p.and(IsPositive).explain(&0), Some("positive".to_owned()));
+}
+
+#[test]
+fn verify() {
+  let p = IsNonEmpty.and(InBounds::<3, 16>.map(|s: &String| s.len()));
+
+  assert!(p.verify(&"alice".to_owned()).is_ok());
+
+  let err = p.verify(&"al".to_owned()).unwrap_err();
+  assert_eq!(err.reason(), Some("between 3 and 16"));
+  assert_eq!(err.file(), file!());
+  assert_eq!(err.line(), line!() - 3);
+  assert_eq!(err.module_path(), None);
+}
+
+#[test]
+fn ranges() {
+  use try_guard::predicates::{between, in_range};
+
+  assert!(in_range(1..3).test(&1));
+  assert!(!in_range(1..3).test(&3));
+  assert!(in_range(..=3.5).test(&3.5));
+  assert_eq!(Predicate::<i32>::describe(&in_range(1..3)), "in 1..3");
+
+  assert!(between('a', 'z').test(&'q'));
+  assert!(!between(1.5, 2.5).test(&3.));
+  assert_eq!(
+    between(1, 3).explain(&4),
+    Some("between 1 and 3".to_owned())
+  );
+}
+
+#[test]
+fn collections() {
+  use try_guard::predicates::{all_unique, len_between, non_empty};
+
+  assert!(non_empty().test(&vec![1]));
+  assert!(!non_empty().test(""));
+
+  assert!(len_between(1, 2).test("ab"));
+  assert!(!len_between(1, 2).test(&vec![1, 2, 3]));
+  assert_eq!(
+    Predicate::<str>::describe(&len_between(1, 2)),
+    "of length between 1 and 2"
+  );
+
+  assert!(all_unique().test(&vec![1, 2, 3]));
+  assert!(!all_unique().test(&[1, 2, 1][..]));
+  assert_eq!(
+    Predicate::<[u8]>::describe(&all_unique()),
+    "without duplicates"
+  );
+}
+
+#[test]
+fn strings() {
+  use try_guard::predicates::{is_ascii, max_bytes, no_control_chars, trimmed};
+
+  assert!(is_ascii().test("abc"));
+  assert!(!is_ascii().test("é"));
+
+  assert!(max_bytes(2).test("é"));
+  assert!(!max_bytes(2).test(&"abc".to_owned()));
+  assert_eq!(Predicate::<str>::describe(&max_bytes(2)), "at most 2 bytes");
+
+  assert!(no_control_chars().test("a b"));
+  assert!(!no_control_chars().test("a\tb"));
+
+  assert!(trimmed().test("a b"));
+  assert!(!trimmed().test(" a"));
+  assert!(!trimmed().test("a\n"));
+}
+
+#[test]
+fn numbers() {
+  use try_guard::predicates::{finite, multiple_of, positive};
+
+  assert!(finite().test(&1.));
+  assert!(!finite().test(&f64::NAN));
+  assert!(!finite().test(&f32::INFINITY));
+
+  assert!(positive().test(&1u8));
+  assert!(!positive().test(&-1.));
+
+  assert!(multiple_of(3).test(&9));
+  assert!(!multiple_of(3).test(&10));
+  assert!(multiple_of(-1).test(&i32::MIN));
+  assert!(multiple_of(0).test(&0));
+  assert!(!multiple_of(0u8).test(&1));
+  assert_eq!(
+    multiple_of(3).explain(&10),
+    Some("a multiple of 3".to_owned())
+  );
+}
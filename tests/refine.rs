@@ -0,0 +1,84 @@
+use std::convert::{TryFrom, TryInto};
+
+use try_guard::predicates::{IsNonEmpty, IsPositive};
+use try_guard::{Bounded, GuardError, Guarded, NonEmpty, Positive, Predicate};
+
+#[derive(Default)]
+struct IsEven;
+
+impl Predicate<u32> for IsEven {
+  fn test(&self, value: &u32) -> bool {
+    value % 2 == 0
+  }
+
+  fn describe(&self) -> String {
+    "even".to_owned()
+  }
+}
+
+#[test]
+fn try_new() {
+  let even = Guarded::<u32, IsEven>::try_new(4).unwrap();
+  assert_eq!(*even.get(), 4);
+  assert_eq!(even.into_inner(), 4);
+
+  let err = Guarded::<u32, IsEven>::try_new(3).unwrap_err();
+  assert_eq!(err.reason(), Some("even"));
+  assert_eq!(err.file(), file!());
+  assert_eq!(err.line(), line!() - 3);
+  assert_eq!(err.module_path(), None);
+}
+
+#[test]
+fn try_from() {
+  let even = Guarded::<u32, IsEven>::try_from(4).unwrap();
+  assert_eq!(*even, 4);
+
+  let name: Result<NonEmpty<String>, GuardError> = String::new().try_into();
+  assert!(name.is_err());
+}
+
+#[test]
+fn deref_as_ref() {
+  let name = NonEmpty::try_new("Alice".to_owned()).unwrap();
+  assert_eq!(name.len(), 5);
+
+  let path: &std::path::Path = name.as_ref();
+  assert_eq!(path, std::path::Path::new("Alice"));
+}
+
+#[test]
+fn non_empty() {
+  assert!(NonEmpty::try_new(vec![1]).is_ok());
+  assert!(NonEmpty::<Vec<u8>>::try_new(Vec::new()).is_err());
+  assert!(NonEmpty::try_new("").is_err());
+  assert!(IsNonEmpty.test("a"));
+  assert!(!IsNonEmpty.test(&[] as &[u8]));
+}
+
+#[test]
+fn positive() {
+  assert!(Positive::try_new(1i64).is_ok());
+  assert!(Positive::try_new(0i64).is_err());
+  assert!(Positive::try_new(-0.5f64).is_err());
+  assert!(IsPositive.test(&0.5f32));
+}
+
+#[test]
+fn bounded() {
+  assert!(Bounded::<u32, 1, 100>::try_new(1).is_ok());
+  assert!(Bounded::<u32, 1, 100>::try_new(100).is_ok());
+  assert!(Bounded::<u32, 1, 100>::try_new(0).is_err());
+  assert!(Bounded::<u32, 1, 100>::try_new(101).is_err());
+  assert!(Bounded::<u128, 0, 1>::try_new(u128::MAX).is_err());
+  assert!(Bounded::<i8, -10, 10>::try_new(-10).is_ok());
+}
+
+#[test]
+fn traits() {
+  let a = Positive::try_new(1u8).unwrap();
+  let b = a;
+  assert_eq!(a, b);
+  assert!(a <= b);
+  assert_eq!(format!("{:?} {}", a, b), "1 1");
+}
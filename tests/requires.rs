@@ -0,0 +1,74 @@
+use try_guard::{requires, GuardFailed};
+
+#[requires(x > 0)]
+fn positive(x: i32) -> Option<i32> {
+  Some(x)
+}
+
+#[requires(!s.is_empty())]
+#[requires(s.len() < 4)]
+fn short(s: &str) -> Result<usize, GuardFailed> {
+  Ok(s.len())
+}
+
+#[derive(Debug, PartialEq)]
+enum Error {
+  Negative,
+  Zero,
+}
+
+#[requires(x >= 0, Error::Negative)]
+#[try_guard::requires(x != 0, Error::Zero)]
+fn custom_error(x: i32) -> Result<i32, Error> {
+  Ok(100 / x)
+}
+
+#[requires(x < 10 else { return -1 })]
+fn else_block(x: i32) -> i32 {
+  x
+}
+
+struct Counter(u32);
+
+impl Counter {
+  #[requires(self.0 < 3)]
+  fn incr(&mut self) -> Option<u32> {
+    self.0 += 1;
+    Some(self.0)
+  }
+}
+
+#[test]
+fn option() {
+  assert_eq!(positive(1), Some(1));
+  assert_eq!(positive(0), None);
+}
+
+#[test]
+fn stacked() {
+  assert_eq!(short("abc"), Ok(3));
+  assert_eq!(short(""), Err(GuardFailed));
+  assert_eq!(short("abcd"), Err(GuardFailed));
+}
+
+#[test]
+fn stacked_in_order() {
+  assert_eq!(custom_error(5), Ok(20));
+  assert_eq!(custom_error(-1), Err(Error::Negative));
+  assert_eq!(custom_error(0), Err(Error::Zero));
+}
+
+#[test]
+fn else_form() {
+  assert_eq!(else_block(3), 3);
+  assert_eq!(else_block(30), -1);
+}
+
+#[test]
+fn method() {
+  let mut counter = Counter(0);
+  assert_eq!(counter.incr(), Some(1));
+  assert_eq!(counter.incr(), Some(2));
+  assert_eq!(counter.incr(), Some(3));
+  assert_eq!(counter.incr(), None);
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use try_guard::predicates::IsNonEmpty;
+use try_guard::{Bounded, NonEmpty, Positive};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Config {
+  name: NonEmpty<String>,
+  workers: Bounded<u8, 1, 64>,
+  timeout: Positive<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Plain {
+  #[serde(deserialize_with = "try_guard::serde::guarded::<_, IsNonEmpty, _>")]
+  tags: Vec<String>,
+  #[serde(deserialize_with = "try_guard::serde::non_empty")]
+  name: String,
+  #[serde(deserialize_with = "try_guard::serde::positive")]
+  timeout: f64,
+  #[serde(deserialize_with = "try_guard::serde::bounded::<_, 1, 64, _>")]
+  workers: u8,
+}
+
+#[test]
+fn round_trip() {
+  let json = r#"{"name":"app","workers":8,"timeout":1.5}"#;
+  let config: Config = serde_json::from_str(json).unwrap();
+
+  assert_eq!(config.name.as_str(), "app");
+  assert_eq!(*config.workers, 8);
+  assert_eq!(*config.timeout, 1.5);
+  assert_eq!(serde_json::to_string(&config).unwrap(), json);
+}
+
+#[test]
+fn guarded_failure() {
+  let error = serde_json::from_str::<Config>(r#"{"name":"","workers":8,"timeout":1.5}"#)
+    .unwrap_err()
+    .to_string();
+  assert!(error.contains("expected non-empty"), "{}", error);
+
+  let error = serde_json::from_str::<Config>(r#"{"name":"app","workers":65,"timeout":1.5}"#)
+    .unwrap_err()
+    .to_string();
+  assert!(error.contains("expected between 1 and 64"), "{}", error);
+
+  assert!(serde_json::from_str::<Config>(r#"{"name":"app","workers":8,"timeout":0}"#).is_err());
+}
+
+#[test]
+fn deserialize_with() {
+  let json = r#"{"tags":["a"],"name":"app","timeout":1.5,"workers":8}"#;
+  let plain: Plain = serde_json::from_str(json).unwrap();
+  assert_eq!(plain.tags, ["a"]);
+  assert_eq!(plain.name, "app");
+  assert_eq!(plain.timeout, 1.5);
+  assert_eq!(plain.workers, 8);
+
+  for json in &[
+    r#"{"tags":[],"name":"app","timeout":1.5,"workers":8}"#,
+    r#"{"tags":["a"],"name":"","timeout":1.5,"workers":8}"#,
+    r#"{"tags":["a"],"name":"app","timeout":-1,"workers":8}"#,
+    r#"{"tags":["a"],"name":"app","timeout":1.5,"workers":0}"#,
+  ] {
+    assert!(serde_json::from_str::<Plain>(json).is_err(), "{}", json);
+  }
+}
@@ -0,0 +1,138 @@
+use try_guard::{contract, GuardFailed};
+
+#[contract]
+trait Stack {
+  type Item;
+
+  fn len(&self) -> usize;
+
+  #[requires(item.is_some())]
+  #[ensures(self.len() == old(self.len()) + 1)]
+  fn push(&mut self, item: Option<Self::Item>) -> Result<(), GuardFailed>;
+
+  #[requires(self.len() > 0)]
+  fn pop(&mut self) -> Option<Self::Item>;
+
+  #[ensures(*ret <= 10)]
+  fn capacity(&self) -> Option<usize> {
+    Some(10)
+  }
+}
+
+struct Good(Vec<u8>);
+
+impl Stack for Good {
+  type Item = u8;
+
+  fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  fn push(&mut self, item: Option<u8>) -> Result<(), GuardFailed> {
+    self.0.extend(item);
+    Ok(())
+  }
+
+  fn pop(&mut self) -> Option<u8> {
+    self.0.pop()
+  }
+}
+
+struct Bad(Vec<u8>);
+
+impl Stack for Bad {
+  type Item = u8;
+
+  fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  fn push(&mut self, _: Option<u8>) -> Result<(), GuardFailed> {
+    Ok(())
+  }
+
+  fn pop(&mut self) -> Option<u8> {
+    Some(0)
+  }
+
+  fn capacity(&self) -> Option<usize> {
+    Some(100)
+  }
+}
+
+#[contract]
+trait Parse<T>
+where
+  T: Copy,
+{
+  #[requires(!input.is_empty())]
+  fn parse(input: &str) -> Option<T>;
+
+  #[ensures(ret.len() == n)]
+  fn repeat(self, _: T, n: usize) -> Option<Vec<T>>;
+
+  #[requires(!input.is_empty())]
+  fn parse_with<F: Fn(&str) -> Option<T>>(input: &str, f: F) -> Option<T> {
+    f(input)
+  }
+}
+
+impl Parse<u32> for () {
+  fn parse(input: &str) -> Option<u32> {
+    input.parse().ok()
+  }
+
+  fn repeat(self, x: u32, n: usize) -> Option<Vec<u32>> {
+    Some(vec![x; n + 1])
+  }
+}
+
+#[test]
+fn good() {
+  let mut stack = Good(Vec::new());
+  assert_eq!(stack.checked_push(Some(1)), Ok(()));
+  assert_eq!(stack.checked_pop(), Some(1));
+  assert_eq!(stack.checked_pop(), None);
+  assert_eq!(stack.checked_push(None), Err(GuardFailed));
+  assert_eq!(stack.checked_capacity(), Some(10));
+}
+
+#[test]
+fn bad() {
+  let mut stack = Bad(Vec::new());
+
+  // unchecked methods trust the implementor
+  assert_eq!(stack.push(Some(1)), Ok(()));
+  assert_eq!(stack.pop(), Some(0));
+
+  assert_eq!(stack.checked_push(Some(1)), Err(GuardFailed));
+  assert_eq!(stack.checked_pop(), None);
+  assert_eq!(stack.checked_capacity(), None);
+}
+
+#[test]
+fn dyn_trait() {
+  let mut stack: Box<dyn StackChecked<Item = u8>> = Box::new(Good(Vec::new()));
+  assert_eq!(stack.checked_push(Some(1)), Ok(()));
+  assert_eq!(stack.checked_pop(), Some(1));
+}
+
+#[test]
+fn generic_trait() {
+  assert_eq!(<() as ParseChecked<u32>>::checked_parse("3"), Some(3));
+  assert_eq!(<() as ParseChecked<u32>>::checked_parse(""), None);
+  assert_eq!(().checked_repeat(3, 2), None);
+}
+
+#[test]
+fn fn_bound() {
+  let parse = |input: &str| input.parse().ok();
+  assert_eq!(
+    <() as ParseChecked<u32>>::checked_parse_with("3", parse),
+    Some(3)
+  );
+  assert_eq!(
+    <() as ParseChecked<u32>>::checked_parse_with("", parse),
+    None
+  );
+}
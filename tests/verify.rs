@@ -1,6 +1,6 @@
-#![cfg_attr(feature = "test-nightly", feature(try_trait), feature(try_blocks))]
+#![cfg_attr(feature = "nightly", feature(try_blocks))]
 
-use try_guard::verify;
+use try_guard::{verify, verify_or, verify_or_else, verify_then};
 
 #[test]
 fn verify_success() {
@@ -14,7 +14,55 @@ fn verify_failure() {
   assert_eq!(foo, None);
 }
 
-#[cfg(feature = "test-nightly")]
+#[test]
+fn verify_value_success() {
+  let foo = verify!(1 < 2 => 10);
+  assert_eq!(foo, Some(10));
+}
+
+#[test]
+fn verify_value_failure() {
+  let mut evaluated = false;
+  let foo = verify!(1 > 2 => {
+    evaluated = true;
+    10
+  });
+
+  assert_eq!(foo, None);
+  assert!(!evaluated);
+}
+
+#[test]
+fn verify_then() {
+  assert_eq!(verify_then!(1 < 2, 10), verify!(1 < 2 => 10));
+  assert_eq!(verify_then!(1 > 2, 10), None);
+}
+
+#[test]
+fn verify_or_success() {
+  let foo: Result<(), &str> = verify_or!(1 < 2, "nope");
+  assert_eq!(foo, Ok(()));
+}
+
+#[test]
+fn verify_or_failure() {
+  let foo = verify_or!(1 > 2, "nope");
+  assert_eq!(foo, Err("nope"));
+}
+
+#[test]
+fn verify_or_else_success() {
+  let foo: Result<(), String> = verify_or_else!(1 < 2, || panic!("evaluated"));
+  assert_eq!(foo, Ok(()));
+}
+
+#[test]
+fn verify_or_else_failure() {
+  let foo = verify_or_else!(1 > 2, || "nope".to_owned());
+  assert_eq!(foo, Err("nope".to_owned()));
+}
+
+#[cfg(feature = "nightly")]
 mod nightly {
   use super::*;
 
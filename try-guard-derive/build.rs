@@ -0,0 +1,23 @@
+//! Detect the features of the compiler.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+  let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+  let minor = Command::new(rustc)
+    .arg("--version")
+    .output()
+    .ok()
+    .and_then(|output| String::from_utf8(output.stdout).ok())
+    .and_then(|version| version.split('.').nth(1)?.parse::<u32>().ok());
+
+  if minor.map_or(false, |minor| minor >= 80) {
+    println!("cargo:rustc-check-cfg=cfg(span_locations)");
+  }
+
+  // the locations and source text of spans are stable since Rust 1.88
+  if minor.map_or(false, |minor| minor >= 88) {
+    println!("cargo:rustc-cfg=span_locations");
+  }
+}
@@ -0,0 +1,341 @@
+//! Function contracts: `#[requires]` and `#[ensures]`.
+
+use proc_macro::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+
+use crate::tokens::{
+  error, expand, is_ident, is_punct, source, split_first_comma, string, Attribute, Cursor,
+};
+
+/// Contract clause of a function.
+enum Clause {
+  /// `#[requires(cond)]`, `#[requires(cond, err)]` or `#[requires(cond else { … })]`.
+  Requires(Vec<TokenTree>),
+  /// `#[ensures(cond)]`, `#[ensures(cond, err)]` or `#[ensures(cond else { … })]`, where `cond`
+  /// can refer to the returned value as `ret` and to values on entry as `old(expr)`.
+  Ensures(Vec<TokenTree>),
+}
+
+impl Clause {
+  fn parse(attr: &str, args: Vec<TokenTree>) -> Option<Self> {
+    match attr {
+      "requires" => Some(Clause::Requires(args)),
+      "ensures" => Some(Clause::Ensures(args)),
+      _ => None,
+    }
+  }
+}
+
+/// Expand a contract attribute.
+///
+/// Stacked contract attributes are all expanded at once by the outermost one, so that their
+/// clauses are checked in the order they’re written in.
+pub fn expand_contract(attr: &str, args: TokenStream, item: TokenStream) -> TokenStream {
+  let clause = Clause::parse(attr, args.into_iter().collect()).unwrap();
+
+  match parse(vec![clause], item) {
+    Ok(tokens) => tokens,
+    Err((span, msg)) => error(span, msg),
+  }
+}
+
+/// Expand the contract attributes of a function.
+pub fn expand_fn(item: TokenStream) -> Result<TokenStream, (Span, &'static str)> {
+  parse(Vec::new(), item)
+}
+
+/// Whether an attribute is a contract attribute.
+pub fn is_contract(attr: &Attribute) -> bool {
+  attr.is("requires") || attr.is("ensures")
+}
+
+fn parse(mut clauses: Vec<Clause>, item: TokenStream) -> Result<TokenStream, (Span, &'static str)> {
+  let mut cursor = Cursor::new(item);
+  let mut attrs = TokenStream::new();
+
+  for attr in cursor.attributes() {
+    let clause = ["requires", "ensures"]
+      .iter()
+      .filter(|name| attr.is(name))
+      .find_map(|name| Clause::parse(name, attr.args.as_ref()?.stream().into_iter().collect()));
+
+    match clause {
+      Some(clause) => clauses.push(clause),
+      None => attrs.extend(attr.tokens),
+    }
+  }
+
+  let (signature, body) = cursor.body();
+
+  if !signature.iter().any(|token| is_ident(token, "fn")) {
+    return Err((Span::call_site(), "contracts can only be put on functions"));
+  }
+
+  let body = body.ok_or((Span::call_site(), "contracts require a function body"))?;
+
+  if is_async(&signature) && clauses.iter().any(|c| matches!(c, Clause::Ensures(_))) {
+    return Err((
+      Span::call_site(),
+      "#[ensures] doesn’t support async functions",
+    ));
+  }
+
+  let mut preconditions = Vec::new();
+  let mut postconditions = Vec::new();
+  let mut checks = TokenStream::new();
+  let mut snapshots = TokenStream::new();
+  let mut post_checks = TokenStream::new();
+
+  for clause in &clauses {
+    match clause {
+      Clause::Requires(args) => {
+        preconditions.push(source(condition(args)));
+        checks.extend(expand(
+          "::try_guard::guard!($args);",
+          &[("args", args.iter().cloned().collect())],
+        ));
+      }
+
+      Clause::Ensures(args) => {
+        postconditions.push(source(condition(args)));
+
+        let mut olds = Vec::new();
+        let args = replace_old(args.iter().cloned().collect(), &mut olds);
+
+        for old in olds {
+          snapshots.extend(expand(
+            "let $name = ::core::clone::Clone::clone(&$expr);",
+            &[("name", old.0), ("expr", old.1)],
+          ));
+        }
+
+        post_checks.extend(expand("::try_guard::guard!($args);", &[("args", args)]));
+      }
+    }
+  }
+
+  let body = if postconditions.is_empty() {
+    body.stream()
+  } else {
+    let checks = expand(
+      "
+      {
+        #[allow(unused_imports)]
+        use ::try_guard::__private::{Succeeded as _, SucceededFallback as _};
+
+        if let ::core::option::Option::Some(ret) =
+          (&::try_guard::__private::Output(&__guard_ret)).succeeded()
+        {
+          $post_checks
+        }
+      }
+      ",
+      &[("post_checks", post_checks)],
+    );
+
+    let mut body_stream = snapshots;
+    body_stream.extend(wrap_body(&signature, body, checks));
+    body_stream
+  };
+
+  let mut docs = doc_attrs("# Preconditions", &preconditions);
+  docs.extend(doc_attrs("# Postconditions", &postconditions));
+
+  Ok(expand(
+    "$attrs $docs $signature { $checks $body }",
+    &[
+      ("attrs", attrs),
+      ("docs", docs),
+      ("signature", signature.into_iter().collect()),
+      ("checks", checks),
+      ("body", body),
+    ],
+  ))
+}
+
+/// Run `body`, then `checks` — which can refer to the returned value as `__guard_ret` — before
+/// returning.
+///
+/// The body is wrapped in a closure so that returning early from it still runs `checks`. If the
+/// function returns a borrow and takes a `&mut` reference, the closure moves the reference so that
+/// the borrow can outlive the closure; `checks` then can’t use it.
+pub fn wrap_body(signature: &[TokenTree], body: Group, checks: TokenStream) -> TokenStream {
+  let ret_ty = return_type(signature);
+  let move_ = if returns_borrow(signature) && params(signature).map_or(false, |p| mut_borrows(&p)) {
+    expand("move", &[])
+  } else {
+    TokenStream::new()
+  };
+
+  let closure = if ret_ty.iter().any(|token| is_ident(token, "impl")) {
+    expand(
+      "$move || $body",
+      &[("move", move_), ("body", TokenTree::Group(body).into())],
+    )
+  } else {
+    expand(
+      "$move || -> $ret_ty $body",
+      &[
+        ("move", move_),
+        ("ret_ty", ret_ty.into_iter().collect()),
+        ("body", TokenTree::Group(body).into()),
+      ],
+    )
+  };
+
+  expand(
+    "
+    let __guard_ret = ::try_guard::__private::call_once($closure);
+    $checks
+    __guard_ret
+    ",
+    &[("closure", closure), ("checks", checks)],
+  )
+}
+
+/// Condition of a clause, without its error or else block.
+pub fn condition(args: &[TokenTree]) -> &[TokenTree] {
+  let (cond, _) = split_first_comma(args);
+  cond.split(|token| is_ident(token, "else")).next().unwrap()
+}
+
+/// Return type of a function signature.
+pub fn return_type(signature: &[TokenTree]) -> Vec<TokenTree> {
+  let after_params = match params_index(signature) {
+    Some(i) => &signature[i + 1..],
+    None => &[],
+  };
+  let arrow = after_params
+    .windows(2)
+    .position(|w| is_punct(&w[0], '-') && is_punct(&w[1], '>'));
+
+  match arrow {
+    Some(i) => after_params[i + 2..]
+      .iter()
+      .take_while(|token| !is_ident(token, "where"))
+      .cloned()
+      .collect(),
+    None => expand("()", &[]).into_iter().collect(),
+  }
+}
+
+/// Whether a function signature is the one of an `async fn`.
+pub fn is_async(signature: &[TokenTree]) -> bool {
+  signature
+    .iter()
+    .take_while(|token| !is_ident(token, "fn"))
+    .any(|token| is_ident(token, "async"))
+}
+
+/// Parameters of a function signature.
+pub fn params(signature: &[TokenTree]) -> Option<Vec<TokenTree>> {
+  match &signature[params_index(signature)?] {
+    TokenTree::Group(group) => Some(group.stream().into_iter().collect()),
+    _ => None,
+  }
+}
+
+/// Index of the parameter list of a function signature.
+///
+/// Parentheses and arrows within the generics — e.g. `F: Fn(u8) -> u8` — are skipped.
+pub fn params_index(signature: &[TokenTree]) -> Option<usize> {
+  let fn_pos = signature.iter().position(|token| is_ident(token, "fn"))?;
+  let mut depth = 0_usize;
+
+  for (i, token) in signature.iter().enumerate().skip(fn_pos) {
+    match token {
+      TokenTree::Punct(punct) if punct.as_char() == '<' => depth += 1,
+      // not the > of ->
+      TokenTree::Punct(punct) if punct.as_char() == '>' && !is_punct(&signature[i - 1], '-') => {
+        depth = depth.saturating_sub(1);
+      }
+      TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis && depth == 0 => {
+        return Some(i);
+      }
+      _ => (),
+    }
+  }
+
+  None
+}
+
+/// Whether a function signature returns a borrow — i.e. a type with a reference or a lifetime
+/// other than `'static`.
+///
+/// Elided lifetimes in paths, such as `std::slice::Iter<u8>`, are not detected.
+pub fn returns_borrow(signature: &[TokenTree]) -> bool {
+  borrows(&return_type(signature))
+}
+
+/// Whether a type has a reference or a lifetime other than `'static`, recursively.
+fn borrows(tokens: &[TokenTree]) -> bool {
+  tokens.iter().enumerate().any(|(i, token)| match token {
+    TokenTree::Punct(punct) if punct.as_char() == '&' => {
+      !tokens.get(i + 1).map_or(false, |next| is_punct(next, '\''))
+    }
+    TokenTree::Punct(punct) if punct.as_char() == '\'' => !tokens
+      .get(i + 1)
+      .map_or(false, |next| is_ident(next, "static")),
+    TokenTree::Group(group) => borrows(&group.stream().into_iter().collect::<Vec<_>>()),
+    _ => false,
+  })
+}
+
+/// Whether tokens have a `&mut` or `&'a mut` reference, recursively.
+fn mut_borrows(tokens: &[TokenTree]) -> bool {
+  tokens.iter().enumerate().any(|(i, token)| match token {
+    TokenTree::Punct(punct) if punct.as_char() == '&' => match tokens.get(i + 1..) {
+      Some([next, ..]) if is_ident(next, "mut") => true,
+      Some([quote, _, next, ..]) if is_punct(quote, '\'') => is_ident(next, "mut"),
+      _ => false,
+    },
+    TokenTree::Group(group) => mut_borrows(&group.stream().into_iter().collect::<Vec<_>>()),
+    _ => false,
+  })
+}
+
+/// Replace every `old(expr)` with a variable, giving the variables and their expressions.
+fn replace_old(stream: TokenStream, olds: &mut Vec<(TokenStream, TokenStream)>) -> TokenStream {
+  let mut out = Vec::new();
+  let mut tokens = stream.into_iter().peekable();
+
+  while let Some(token) = tokens.next() {
+    match token {
+      TokenTree::Ident(ref ident) if ident.to_string() == "old" => match tokens.peek() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+          let name = Ident::new(&format!("__guard_old_{}", olds.len()), ident.span());
+          let expr = TokenTree::Group(group.clone()).into();
+          tokens.next();
+          olds.push((TokenTree::Ident(name.clone()).into(), expr));
+          out.push(TokenTree::Ident(name));
+        }
+
+        _ => out.push(token),
+      },
+
+      TokenTree::Group(group) => {
+        let mut new_group = Group::new(group.delimiter(), replace_old(group.stream(), olds));
+        new_group.set_span(group.span());
+        out.push(TokenTree::Group(new_group));
+      }
+
+      token => out.push(token),
+    }
+  }
+
+  out.into_iter().collect()
+}
+
+/// Documentation section listing conditions.
+pub fn doc_attrs(header: &str, conds: &[String]) -> TokenStream {
+  if conds.is_empty() {
+    return TokenStream::new();
+  }
+
+  let mut lines = vec![String::new(), header.to_owned(), String::new()];
+  lines.extend(conds.iter().map(|cond| format!("  - `{}`", cond)));
+
+  lines
+    .iter()
+    .flat_map(|line| expand("#[doc = $line]", &[("line", string(line))]))
+    .collect()
+}
@@ -0,0 +1,260 @@
+//! Documentation of guard sites: `#[guarded]`.
+
+use proc_macro::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+use crate::contract::return_type;
+use crate::tokens::{error, expand, is_ident, is_punct, source, string, Cursor};
+
+/// Guard found in the body of a function.
+struct Guard {
+  /// Condition, in Markdown.
+  cond: String,
+  /// Source text of the error, if known.
+  error: Option<String>,
+}
+
+pub fn expand_guarded(args: TokenStream, item: TokenStream) -> TokenStream {
+  if let Some(token) = args.into_iter().next() {
+    return error(token.span(), "#[guarded] doesn’t take any argument");
+  }
+
+  let mut cursor = Cursor::new(item);
+  let attrs = cursor.attributes();
+  let (signature, body) = cursor.body();
+
+  let body = match body {
+    Some(body) if signature.iter().any(|token| is_ident(token, "fn")) => body,
+    _ => return error(Span::call_site(), "#[guarded] can only be put on functions"),
+  };
+
+  let mut out = TokenStream::new();
+
+  for attr in attrs {
+    out.extend(attr.tokens);
+  }
+
+  for line in docs(&signature, &body) {
+    out.extend(expand("#[doc = $line]", &[("line", string(&line))]));
+  }
+
+  out.extend(signature);
+  out.extend(Some(TokenTree::Group(body)));
+  out
+}
+
+/// Lines of documentation `#[guarded]` appends to a function, as a `&[&str]` expression.
+#[cfg(feature = "test-hooks")]
+pub fn expand_docs(item: TokenStream) -> TokenStream {
+  let mut cursor = Cursor::new(item);
+  cursor.attributes();
+
+  let (signature, body) = match cursor.body() {
+    (signature, Some(body)) => (signature, body),
+    _ => return error(Span::call_site(), "expected a function"),
+  };
+
+  let lines = docs(&signature, &body)
+    .iter()
+    .flat_map(|line| expand("$line,", &[("line", string(line))]))
+    .collect();
+
+  expand("&[$lines]", &[("lines", lines)])
+}
+
+/// Lines of documentation listing the guards of a function.
+fn docs(signature: &[TokenTree], body: &Group) -> Vec<String> {
+  let mut guards = Vec::new();
+  scan(body.stream(), &mut guards);
+
+  let ret_ty = return_type(signature);
+  let ret_name = ret_ty
+    .iter()
+    .take_while(|token| !is_punct(token, '<'))
+    .filter(|token| matches!(token, TokenTree::Ident(_)))
+    .last()
+    .map(ToString::to_string);
+
+  let mut lines = Vec::new();
+
+  if !guards.is_empty() {
+    match ret_name.as_deref() {
+      Some("Result") => {
+        lines.extend(vec![String::new(), "# Errors".to_owned(), String::new()]);
+        lines.extend(guards.iter().map(|guard| match guard.error {
+          Some(ref error) => format!("  - `{}` unless {}.", error, guard.cond),
+          None => format!("  - Unless {}.", guard.cond),
+        }));
+      }
+
+      Some("Option") => {
+        lines.extend(vec![
+          String::new(),
+          "# Preconditions".to_owned(),
+          String::new(),
+        ]);
+        lines.push("Returns `None` unless:".to_owned());
+        lines.push(String::new());
+        lines.extend(guards.iter().map(|guard| format!("  - {}", guard.cond)));
+      }
+
+      _ => {
+        lines.extend(vec![
+          String::new(),
+          "# Preconditions".to_owned(),
+          String::new(),
+        ]);
+        lines.extend(guards.iter().map(|guard| format!("  - {}", guard.cond)));
+      }
+    }
+  }
+
+  lines
+}
+
+/// Find the guard invocations in `stream`, recursively.
+///
+/// The bodies of nested functions and closures are skipped: their guards don’t return from the
+/// function.
+fn scan(stream: TokenStream, guards: &mut Vec<Guard>) {
+  let tokens = stream.into_iter().collect::<Vec<_>>();
+  let mut i = 0;
+
+  while i < tokens.len() {
+    let token = &tokens[i];
+    i += 1;
+
+    // fn items, but not fn pointer types
+    if is_ident(token, "fn") && matches!(tokens.get(i), Some(TokenTree::Ident(_))) {
+      i += tokens[i..]
+        .iter()
+        .position(|token| matches!(token, TokenTree::Group(g) if g.delimiter() == Delimiter::Brace))
+        .map_or(tokens.len() - i, |body| body + 1);
+      continue;
+    }
+
+    if is_punct(token, '|') && is_closure_start(i.checked_sub(2).map(|i| &tokens[i])) {
+      // parameters, then the body up to the end of the expression
+      i += tokens[i..]
+        .iter()
+        .position(|token| is_punct(token, '|'))
+        .map_or(tokens.len() - i, |params| params + 1);
+      i += tokens[i..]
+        .iter()
+        .position(|token| is_punct(token, ',') || is_punct(token, ';'))
+        .unwrap_or(tokens.len() - i);
+      continue;
+    }
+
+    let group = match token {
+      TokenTree::Group(group) => group,
+      _ => continue,
+    };
+
+    let name = match (
+      i.checked_sub(3).map(|i| &tokens[i]),
+      i.checked_sub(2).map(|i| &tokens[i]),
+    ) {
+      (Some(TokenTree::Ident(name)), Some(bang)) if is_punct(bang, '!') => name.to_string(),
+      _ => {
+        scan(group.stream(), guards);
+        continue;
+      }
+    };
+
+    let args = group.stream().into_iter().collect::<Vec<_>>();
+
+    if let Some(guard) = guard(&name, &args) {
+      guards.push(guard);
+    } else if group.delimiter() != Delimiter::None {
+      // guards can be nested in other macros, such as a loop in a vec![…]
+      scan(group.stream(), guards);
+    }
+  }
+}
+
+/// Whether a `|` preceded by `prev` starts a closure, rather than being a binary operator.
+fn is_closure_start(prev: Option<&TokenTree>) -> bool {
+  match prev {
+    None => true,
+    // the second | of ||
+    Some(TokenTree::Punct(punct)) => !matches!(punct.as_char(), '?' | '|'),
+    Some(token) => is_ident(token, "move") || is_ident(token, "return"),
+  }
+}
+
+/// Condition and error of a guard invocation.
+fn guard(name: &str, args: &[TokenTree]) -> Option<Guard> {
+  let parts = args
+    .split(|token| is_punct(token, ','))
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>();
+
+  let op = match name {
+    "guard_eq" => "==",
+    "guard_ne" => "!=",
+    "guard_lt" => "<",
+    "guard_le" => "<=",
+    "guard_gt" => ">",
+    "guard_ge" => ">=",
+
+    "guard" | "guard_report" => {
+      let default = if name == "guard" {
+        "GuardFailed"
+      } else {
+        "GuardError"
+      };
+
+      let (cond, error) = match parts.as_slice() {
+        [cond] => match cond.iter().position(|token| is_ident(token, "else")) {
+          Some(i) => (&cond[..i], None),
+          None => (*cond, Some(default.to_owned())),
+        },
+        // commas can appear in the condition, as function arguments for instance, but only
+        // within groups
+        [cond, error] => (*cond, Some(source(error))),
+        _ => return None,
+      };
+
+      return Some(Guard {
+        cond: format!("`{}`", source(cond)),
+        error,
+      });
+    }
+
+    "guard_let" => {
+      let (pat, error) = match parts.as_slice() {
+        [pat] => (*pat, "GuardFailed".to_owned()),
+        [pat, error] => (*pat, source(error)),
+        _ => return None,
+      };
+
+      // skip the = of ..= in range patterns
+      let eq = pat
+        .iter()
+        .enumerate()
+        .position(|(i, token)| is_punct(token, '=') && (i == 0 || !is_punct(&pat[i - 1], '.')))?;
+
+      return Some(Guard {
+        cond: format!(
+          "`{}` matches `{}`",
+          source(&pat[eq + 1..]),
+          source(&pat[..eq])
+        ),
+        error: Some(error),
+      });
+    }
+
+    _ => return None,
+  };
+
+  let (left, right, error) = match parts.as_slice() {
+    [left, right] => (left, right, "GuardError".to_owned()),
+    [left, right, error] => (left, right, source(error)),
+    _ => return None,
+  };
+
+  Some(Guard {
+    cond: format!("`{} {} {}`", source(left), op, source(right)),
+    error: Some(error),
+  })
+}
@@ -0,0 +1,215 @@
+//! Type invariants: `#[invariant]`.
+
+use proc_macro::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+use crate::contract::{
+  condition, doc_attrs, is_async, params, return_type, returns_borrow, wrap_body,
+};
+use crate::report;
+use crate::tokens::{error, expand, is_ident, is_punct, items, source, split_first_comma, Cursor};
+
+/// What to do when an infallible method violates an invariant.
+enum Policy {
+  Panic,
+  Log,
+}
+
+/// `#[invariant(cond)]` or `#[invariant(cond, policy = …)]`.
+struct Invariant {
+  cond: TokenStream,
+  text: String,
+  policy: Policy,
+}
+
+impl Invariant {
+  fn parse(args: Vec<TokenTree>) -> Result<Self, (Span, &'static str)> {
+    let (_, rest) = split_first_comma(&args);
+    let cond = condition(&args);
+
+    if cond.is_empty() {
+      return Err((Span::call_site(), "expected an invariant"));
+    }
+
+    let policy = match rest {
+      None | Some([]) => Policy::Panic,
+      Some([key, eq, value]) if is_ident(key, "policy") && is_punct(eq, '=') => {
+        if is_ident(value, "panic") {
+          Policy::Panic
+        } else if is_ident(value, "log") {
+          Policy::Log
+        } else {
+          return Err((value.span(), "expected panic or log"));
+        }
+      }
+      Some(rest) => return Err((rest[0].span(), "expected policy = panic or policy = log")),
+    };
+
+    Ok(Invariant {
+      cond: cond.iter().cloned().collect(),
+      text: source(cond),
+      policy,
+    })
+  }
+}
+
+pub fn expand_invariant(args: TokenStream, item: TokenStream) -> TokenStream {
+  match parse(args, item) {
+    Ok(tokens) => tokens,
+    Err((span, msg)) => error(span, msg),
+  }
+}
+
+fn parse(args: TokenStream, item: TokenStream) -> Result<TokenStream, (Span, &'static str)> {
+  let mut cursor = Cursor::new(item);
+  let mut invariants = vec![Invariant::parse(args.into_iter().collect())?];
+  let mut attrs = TokenStream::new();
+
+  // stacked invariants are all expanded by the outermost one
+  for attr in cursor.attributes() {
+    match attr.args {
+      Some(ref args) if attr.is("invariant") => {
+        invariants.push(Invariant::parse(args.stream().into_iter().collect())?);
+      }
+
+      _ => attrs.extend(attr.tokens),
+    }
+  }
+
+  let (header, items) = cursor.body();
+
+  if !header.iter().any(|token| is_ident(token, "impl")) {
+    return Err((
+      Span::call_site(),
+      "invariants can only be put on impl blocks",
+    ));
+  }
+
+  let items = items.ok_or((Span::call_site(), "expected an impl block"))?;
+  let texts = invariants
+    .iter()
+    .map(|invariant| invariant.text.clone())
+    .collect::<Vec<_>>();
+
+  Ok(expand(
+    "$attrs $docs $header { $items }",
+    &[
+      ("attrs", attrs),
+      ("docs", doc_attrs("# Invariants", &texts)),
+      ("header", header.into_iter().collect()),
+      ("items", impl_items(items.stream(), &invariants)),
+    ],
+  ))
+}
+
+/// Items of the impl block, with invariants checked at the end of `&mut self` methods.
+///
+/// Methods returning a borrow are skipped, as the invariants can’t be checked while it’s alive, as
+/// well as async methods and methods marked with `#[invariant(skip)]`.
+fn impl_items(stream: TokenStream, invariants: &[Invariant]) -> TokenStream {
+  let mut out = TokenStream::new();
+
+  for item in items(stream) {
+    let mut skip = false;
+
+    for attr in item.attrs {
+      match attr.args {
+        Some(ref args) if attr.is("invariant") && is_skip(args) => skip = true,
+        _ => out.extend(attr.tokens),
+      }
+    }
+
+    let checked = !skip
+      && is_mut_method(&item.tokens)
+      && !is_async(&item.tokens)
+      && !returns_borrow(&item.tokens);
+
+    match item.body {
+      Some(body) if checked => {
+        let checks = checks(invariants, is_fallible(&item.tokens));
+        let body = Group::new(Delimiter::Brace, wrap_body(&item.tokens, body, checks));
+        out.extend(item.tokens);
+        out.extend(Some(TokenTree::Group(body)));
+      }
+
+      body => {
+        out.extend(item.tokens);
+        out.extend(body.map(TokenTree::Group));
+      }
+    }
+  }
+
+  out
+}
+
+/// Whether the arguments of an `#[invariant(…)]` attribute are `skip`.
+fn is_skip(args: &Group) -> bool {
+  let args = args.stream().into_iter().collect::<Vec<_>>();
+  matches!(args.as_slice(), [arg] if is_ident(arg, "skip"))
+}
+
+/// Whether an item is a method taking `&mut self`.
+fn is_mut_method(item: &[TokenTree]) -> bool {
+  let params = match params(item) {
+    Some(params) => params,
+    None => return false,
+  };
+
+  let (receiver, _) = split_first_comma(&params);
+  let amp = receiver.iter().position(|token| is_punct(token, '&'));
+  let mut_ = receiver.iter().position(|token| is_ident(token, "mut"));
+  let self_ = receiver.iter().position(|token| is_ident(token, "self"));
+
+  match (amp, mut_, self_) {
+    // &mut self, &'a mut self
+    (Some(0), Some(mut_), Some(self_)) => mut_ < self_,
+    // self: &mut Self
+    (Some(amp), Some(mut_), Some(0)) => amp < mut_,
+    _ => false,
+  }
+}
+
+/// Whether a method returns an `Option` or a `Result`.
+fn is_fallible(item: &[TokenTree]) -> bool {
+  let ret_ty = return_type(item);
+  let name = ret_ty
+    .iter()
+    .take_while(|token| !is_punct(token, '<'))
+    .filter(|token| matches!(token, TokenTree::Ident(_)))
+    .last();
+
+  name.map_or(false, |name| {
+    is_ident(name, "Option") || is_ident(name, "Result")
+  })
+}
+
+/// Checks of the invariants at the end of a method.
+fn checks(invariants: &[Invariant], fallible: bool) -> TokenStream {
+  invariants
+    .iter()
+    .flat_map(|invariant| {
+      let fail = if fallible {
+        "::try_guard::__guard_fail!(error)"
+      } else {
+        match invariant.policy {
+          Policy::Panic => "::try_guard::__private::invariant_panic(error)",
+          Policy::Log => "::try_guard::__private::invariant_log(error)",
+        }
+      };
+
+      expand(
+        "
+        if let ::core::result::Result::Err(error) = $check {
+          $fail;
+        }
+        ",
+        &[
+          (
+            "check",
+            report::check(invariant.cond.clone(), &invariant.text),
+          ),
+          ("fail", fail.parse().unwrap()),
+        ],
+      )
+    })
+    .collect()
+}
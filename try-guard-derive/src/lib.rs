@@ -0,0 +1,62 @@
+//! Procedural macros for [try-guard].
+//!
+//! You shouldn’t depend on this crate directly: its macros are re-exported by [try-guard], which
+//! documents them.
+//!
+//! [try-guard]: https://crates.io/crates/try-guard
+
+extern crate proc_macro;
+
+mod contract;
+mod guarded;
+mod invariant;
+mod report;
+mod tokens;
+mod trait_contract;
+mod validate;
+
+use proc_macro::TokenStream;
+
+// Documented where it’s re-exported, in try-guard.
+#[proc_macro_derive(Validate, attributes(guard))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+  validate::derive(input)
+}
+
+// Documented where it’s re-exported, in try-guard.
+#[proc_macro_attribute]
+pub fn requires(args: TokenStream, item: TokenStream) -> TokenStream {
+  contract::expand_contract("requires", args, item)
+}
+
+// Documented where it’s re-exported, in try-guard.
+#[proc_macro_attribute]
+pub fn ensures(args: TokenStream, item: TokenStream) -> TokenStream {
+  contract::expand_contract("ensures", args, item)
+}
+
+// Documented where it’s re-exported, in try-guard.
+#[proc_macro_attribute]
+pub fn invariant(args: TokenStream, item: TokenStream) -> TokenStream {
+  invariant::expand_invariant(args, item)
+}
+
+// Documented where it’s re-exported, in try-guard.
+#[proc_macro_attribute]
+pub fn contract(args: TokenStream, item: TokenStream) -> TokenStream {
+  trait_contract::expand_trait_contract(args, item)
+}
+
+// Documented where it’s re-exported, in try-guard.
+#[proc_macro_attribute]
+pub fn guarded(args: TokenStream, item: TokenStream) -> TokenStream {
+  guarded::expand_guarded(args, item)
+}
+
+// Used to test #[guarded].
+#[cfg(feature = "test-hooks")]
+#[doc(hidden)]
+#[proc_macro]
+pub fn __guarded_docs(item: TokenStream) -> TokenStream {
+  guarded::expand_docs(item)
+}
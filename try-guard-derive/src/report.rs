@@ -0,0 +1,23 @@
+//! Reports of failed predicates.
+
+use proc_macro::TokenStream;
+
+use crate::tokens::{expand, string};
+
+/// Check of a predicate, as an expression of type `Result<(), GuardError>`.
+///
+/// The predicate is reported as `expr` — typically its source text — rather than stringified.
+pub fn check(pred: TokenStream, expr: &str) -> TokenStream {
+  expand(
+    "
+    ('__guard_check: {
+      ::try_guard::__guard_report!(
+        {break '__guard_check ::core::result::Result::Err} @scan [$pred] [] $pred
+      );
+      ::core::result::Result::Ok(())
+    })
+    .map_err(|error: ::try_guard::GuardError| error.with_expr($expr))
+    ",
+    &[("pred", pred), ("expr", string(expr))],
+  )
+}
@@ -0,0 +1,484 @@
+//! Token helpers.
+//!
+//! This crate doesn’t depend on any parsing or quoting crate, so this module provides the few
+//! things we need to walk and generate token streams.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::iter::FromIterator;
+
+/// Expand a template, replacing every `$name` with the corresponding token stream in `vars`.
+pub fn expand(template: &str, vars: &[(&str, TokenStream)]) -> TokenStream {
+  let stream = template
+    .parse()
+    .expect("templates must be valid token streams");
+  substitute(stream, vars)
+}
+
+fn substitute(stream: TokenStream, vars: &[(&str, TokenStream)]) -> TokenStream {
+  let mut out = Vec::new();
+  let mut tokens = stream.into_iter().peekable();
+
+  while let Some(token) = tokens.next() {
+    match token {
+      TokenTree::Punct(ref punct) if punct.as_char() == '$' => {
+        let var = match tokens.peek() {
+          Some(TokenTree::Ident(ident)) => vars
+            .iter()
+            .find(|(name, _)| ident.to_string() == *name)
+            .map(|(_, value)| value.clone()),
+          _ => None,
+        };
+
+        match var {
+          Some(value) => {
+            tokens.next();
+            out.extend(value);
+          }
+          None => out.push(token),
+        }
+      }
+
+      TokenTree::Group(group) => {
+        let mut new_group = Group::new(group.delimiter(), substitute(group.stream(), vars));
+        new_group.set_span(group.span());
+        out.push(TokenTree::Group(new_group));
+      }
+
+      token => out.push(token),
+    }
+  }
+
+  TokenStream::from_iter(out)
+}
+
+/// `compile_error!` invocation with the given message, located at `span`.
+pub fn error(span: Span, msg: &str) -> TokenStream {
+  let tokens = vec![
+    TokenTree::Ident(Ident::new("compile_error", span)),
+    TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+    TokenTree::Group(Group::new(
+      Delimiter::Parenthesis,
+      TokenStream::from(TokenTree::Literal(Literal::string(msg))),
+    )),
+    TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+  ];
+
+  tokens
+    .into_iter()
+    .map(|mut token| {
+      token.set_span(span);
+      token
+    })
+    .collect()
+}
+
+/// String literal token stream.
+pub fn string(s: &str) -> TokenStream {
+  TokenStream::from(TokenTree::Literal(Literal::string(s)))
+}
+
+/// Whether a token is the given punctuation character.
+pub fn is_punct(token: &TokenTree, c: char) -> bool {
+  match token {
+    TokenTree::Punct(punct) => punct.as_char() == c,
+    _ => false,
+  }
+}
+
+/// Whether a token is the given identifier or keyword.
+pub fn is_ident(token: &TokenTree, s: &str) -> bool {
+  match token {
+    TokenTree::Ident(ident) => ident.to_string() == s,
+    _ => false,
+  }
+}
+
+/// Source text of tokens.
+///
+/// The spacing of the original source is kept, which isn’t the case when stringifying tokens, so
+/// that predicates read as written in documentation and reports. Tokens without source — generated
+/// by another macro, for instance — are stringified, and so are all tokens on compilers where the
+/// source of spans isn’t available.
+#[cfg(span_locations)]
+#[clippy::msrv = "1.88"]
+pub fn source(tokens: &[TokenTree]) -> String {
+  let mut text = String::new();
+  let mut prev_end: Option<Span> = None;
+
+  for token in tokens {
+    let span = token.span();
+
+    if let Some(prev_end) = prev_end {
+      let start = span.start();
+
+      if prev_end.line() != start.line() || prev_end.column() != start.column() {
+        text.push(' ');
+      }
+    }
+
+    match span.source_text() {
+      Some(source) => text.push_str(&source),
+      None => text.push_str(&token.to_string()),
+    }
+
+    prev_end = Some(span.end());
+  }
+
+  text
+}
+
+#[cfg(not(span_locations))]
+pub fn source(tokens: &[TokenTree]) -> String {
+  tokens.iter().cloned().collect::<TokenStream>().to_string()
+}
+
+/// Source text of a span, if available.
+#[cfg(span_locations)]
+#[clippy::msrv = "1.88"]
+pub fn source_text(span: Span) -> Option<String> {
+  span.source_text()
+}
+
+#[cfg(not(span_locations))]
+pub fn source_text(_: Span) -> Option<String> {
+  None
+}
+
+/// Split tokens at the first top-level comma, if any.
+pub fn split_first_comma(tokens: &[TokenTree]) -> (&[TokenTree], Option<&[TokenTree]>) {
+  match tokens.iter().position(|token| is_punct(token, ',')) {
+    Some(i) => (&tokens[..i], Some(&tokens[i + 1..])),
+    None => (tokens, None),
+  }
+}
+
+/// Split tokens on top-level commas.
+///
+/// Commas inside groups are not top-level, and neither are commas inside angle brackets, so that
+/// types such as `HashMap<K, V>` are not split. Empty trailing parts are dropped.
+pub fn split_commas(tokens: Vec<TokenTree>) -> Vec<Vec<TokenTree>> {
+  let mut parts = vec![Vec::new()];
+  let mut depth = 0usize;
+  let mut prev_dash = false;
+
+  for token in tokens {
+    let dash = is_punct(&token, '-');
+
+    if is_punct(&token, '<') {
+      depth += 1;
+    } else if is_punct(&token, '>') && !prev_dash {
+      depth = depth.saturating_sub(1);
+    } else if is_punct(&token, ',') && depth == 0 {
+      parts.push(Vec::new());
+      prev_dash = false;
+      continue;
+    }
+
+    prev_dash = dash;
+    parts.last_mut().unwrap().push(token);
+  }
+
+  if parts.last().map_or(false, Vec::is_empty) {
+    parts.pop();
+  }
+
+  parts
+}
+
+/// Outer attribute, such as `#[guard(nested)]`.
+pub struct Attribute {
+  /// Tokens of the whole attribute, `#` included.
+  pub tokens: Vec<TokenTree>,
+  /// Path of the attribute, as a string without spaces — e.g. `try_guard::requires`.
+  pub path: String,
+  /// Arguments of the attribute, if any — i.e. the content of the parenthesized group following
+  /// the path.
+  pub args: Option<Group>,
+}
+
+impl Attribute {
+  /// Whether the attribute is `name`, `try_guard::name` or `::try_guard::name`.
+  pub fn is(&self, name: &str) -> bool {
+    let path = self.path.trim_start_matches("::");
+    path == name || path.strip_prefix("try_guard::") == Some(name)
+  }
+}
+
+/// Cursor over a list of tokens.
+pub struct Cursor {
+  tokens: Vec<TokenTree>,
+  pos: usize,
+}
+
+impl Cursor {
+  pub fn new(stream: TokenStream) -> Self {
+    Cursor {
+      tokens: stream.into_iter().collect(),
+      pos: 0,
+    }
+  }
+
+  pub fn peek(&self) -> Option<&TokenTree> {
+    self.tokens.get(self.pos)
+  }
+
+  pub fn peek_nth(&self, n: usize) -> Option<&TokenTree> {
+    self.tokens.get(self.pos + n)
+  }
+
+  pub fn next(&mut self) -> Option<TokenTree> {
+    let token = self.tokens.get(self.pos).cloned();
+    self.pos += 1;
+    token
+  }
+
+  /// Span of the current token, or of the call site if there’s none left.
+  pub fn span(&self) -> Span {
+    self.peek().map_or_else(Span::call_site, TokenTree::span)
+  }
+
+  /// Consume the next token if it’s the given punctuation character.
+  pub fn eat_punct(&mut self, c: char) -> bool {
+    if self.peek().map_or(false, |token| is_punct(token, c)) {
+      self.pos += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Consume the next token if it’s the given identifier or keyword.
+  pub fn eat_ident(&mut self, s: &str) -> bool {
+    if self.peek().map_or(false, |token| is_ident(token, s)) {
+      self.pos += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Consume outer attributes.
+  pub fn attributes(&mut self) -> Vec<Attribute> {
+    let mut attrs = Vec::new();
+
+    while self.peek().map_or(false, |token| is_punct(token, '#')) {
+      let group = match self.peek_nth(1) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group.clone(),
+        _ => break,
+      };
+
+      let hash = self.next().unwrap();
+      self.next();
+
+      let mut path = String::new();
+      let mut args = None;
+
+      for token in group.stream() {
+        match token {
+          TokenTree::Group(ref g) if g.delimiter() == Delimiter::Parenthesis && args.is_none() => {
+            args = Some(g.clone());
+          }
+          ref token if args.is_none() && !is_punct(token, '=') => path.push_str(&token.to_string()),
+          _ => break,
+        }
+      }
+
+      attrs.push(Attribute {
+        tokens: vec![hash, TokenTree::Group(group)],
+        path,
+        args,
+      });
+    }
+
+    attrs
+  }
+
+  /// Consume the remaining tokens, splitting a final `{ … }` block — the body of an item — from
+  /// the ones before it.
+  pub fn body(&mut self) -> (Vec<TokenTree>, Option<Group>) {
+    let mut header = Vec::new();
+    let mut body = None;
+
+    while let Some(token) = self.next() {
+      match token {
+        TokenTree::Group(ref group)
+          if group.delimiter() == Delimiter::Brace && self.peek().is_none() =>
+        {
+          body = Some(group.clone());
+        }
+
+        token => header.push(token),
+      }
+    }
+
+    (header, body)
+  }
+
+  /// Consume a visibility, if any.
+  pub fn visibility(&mut self) -> Vec<TokenTree> {
+    let mut vis = Vec::new();
+
+    if self.peek().map_or(false, |token| is_ident(token, "pub")) {
+      vis.push(self.next().unwrap());
+
+      if let Some(TokenTree::Group(group)) = self.peek() {
+        if group.delimiter() == Delimiter::Parenthesis {
+          vis.push(self.next().unwrap());
+        }
+      }
+    }
+
+    vis
+  }
+
+  /// Consume generic parameters, if any, `<` and `>` excluded.
+  pub fn generics(&mut self) -> Option<Vec<TokenTree>> {
+    if !self.eat_punct('<') {
+      return None;
+    }
+
+    let mut params = Vec::new();
+    let mut depth = 1usize;
+    let mut prev_dash = false;
+
+    while let Some(token) = self.next() {
+      if is_punct(&token, '<') {
+        depth += 1;
+      } else if is_punct(&token, '>') && !prev_dash {
+        depth -= 1;
+
+        if depth == 0 {
+          break;
+        }
+      }
+
+      prev_dash = is_punct(&token, '-');
+      params.push(token);
+    }
+
+    Some(params)
+  }
+}
+
+/// Item of a trait or an impl block.
+pub struct Item {
+  pub attrs: Vec<Attribute>,
+  /// Tokens of the item, up to its body or its final `;` included.
+  pub tokens: Vec<TokenTree>,
+  pub body: Option<Group>,
+}
+
+/// Split the content of a trait or an impl block into items.
+pub fn items(stream: TokenStream) -> Vec<Item> {
+  let mut items = Vec::new();
+  let mut cursor = Cursor::new(stream);
+
+  while cursor.peek().is_some() {
+    let attrs = cursor.attributes();
+    let mut tokens = Vec::new();
+    let mut body = None;
+
+    while let Some(token) = cursor.next() {
+      match token {
+        TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace => {
+          body = Some(group.clone());
+          break;
+        }
+
+        ref token if is_punct(token, ';') => {
+          tokens.push(token.clone());
+          break;
+        }
+
+        token => tokens.push(token),
+      }
+    }
+
+    items.push(Item {
+      attrs,
+      tokens,
+      body,
+    });
+  }
+
+  items
+}
+
+/// Generic parameters of an item, split in the forms needed to implement a trait for it.
+#[derive(Default)]
+pub struct Generics {
+  /// Parameters with their bounds but without defaults, for `impl<…>`.
+  pub impl_params: TokenStream,
+  /// Parameters without bounds, for `Type<…>`.
+  pub type_params: TokenStream,
+  /// Names of the type parameters, without lifetimes and consts.
+  pub types: Vec<Ident>,
+}
+
+impl Generics {
+  pub fn new(params: Vec<TokenTree>) -> Self {
+    let mut impl_params = Vec::new();
+    let mut type_params = Vec::new();
+    let mut types = Vec::new();
+
+    for param in split_commas(params) {
+      // strip defaults
+      let mut depth = 0usize;
+      let bounded = param
+        .iter()
+        .take_while(|token| {
+          if is_punct(token, '<') {
+            depth += 1;
+          } else if is_punct(token, '>') {
+            depth = depth.saturating_sub(1);
+          }
+
+          !(depth == 0 && is_punct(token, '='))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+      let name = if is_ident(&bounded[0], "const") {
+        vec![bounded[1].clone()]
+      } else if is_punct(&bounded[0], '\'') {
+        bounded[..2].to_vec()
+      } else {
+        if let TokenTree::Ident(ref ident) = bounded[0] {
+          types.push(ident.clone());
+        }
+
+        vec![bounded[0].clone()]
+      };
+
+      impl_params.extend(bounded);
+      impl_params.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+      type_params.extend(name);
+      type_params.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+    }
+
+    Generics {
+      impl_params: impl_params.into_iter().collect(),
+      type_params: type_params.into_iter().collect(),
+      types,
+    }
+  }
+}
+
+/// Replace every `self` identifier with `replacement`, recursively.
+pub fn replace_self(stream: TokenStream, replacement: &TokenStream) -> TokenStream {
+  stream
+    .into_iter()
+    .flat_map(|token| match token {
+      TokenTree::Ident(ref ident) if ident.to_string() == "self" => replacement.clone(),
+
+      TokenTree::Group(group) => {
+        let mut new_group =
+          Group::new(group.delimiter(), replace_self(group.stream(), replacement));
+        new_group.set_span(group.span());
+        TokenTree::Group(new_group).into()
+      }
+
+      token => token.into(),
+    })
+    .collect()
+}
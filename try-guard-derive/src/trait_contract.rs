@@ -0,0 +1,298 @@
+//! Trait contracts: `#[contract]`.
+
+use proc_macro::{Delimiter, Ident, Span, TokenStream, TokenTree};
+
+use crate::contract::{condition, doc_attrs, expand_fn, is_contract, params_index};
+use crate::tokens::{
+  error, expand, is_ident, is_punct, items, source, split_commas, string, Cursor, Generics,
+};
+
+pub fn expand_trait_contract(args: TokenStream, item: TokenStream) -> TokenStream {
+  if let Some(token) = args.into_iter().next() {
+    return error(token.span(), "#[contract] doesn’t take any argument");
+  }
+
+  match parse(item) {
+    Ok(tokens) => tokens,
+    Err((span, msg)) => error(span, msg),
+  }
+}
+
+fn parse(item: TokenStream) -> Result<TokenStream, (Span, &'static str)> {
+  let mut cursor = Cursor::new(item);
+  let attrs = cursor.attributes();
+  let vis = cursor.visibility();
+  let mut header = Vec::new();
+
+  while let Some(token) = cursor.next() {
+    let is_trait = is_ident(&token, "trait");
+    header.push(token);
+
+    if is_trait {
+      break;
+    }
+  }
+
+  if !header
+    .last()
+    .map_or(false, |token| is_ident(token, "trait"))
+  {
+    return Err((Span::call_site(), "#[contract] can only be put on traits"));
+  }
+
+  let name = match cursor.next() {
+    Some(TokenTree::Ident(ident)) => ident,
+    _ => return Err((cursor.span(), "expected a trait name")),
+  };
+
+  let generics = cursor.generics();
+  let mut where_clause = Vec::new();
+  let mut in_where = false;
+  let mut rest = Vec::new();
+  let mut items_group = None;
+
+  while let Some(token) = cursor.next() {
+    match token {
+      TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace => {
+        items_group = Some(group.clone());
+        break;
+      }
+
+      token => {
+        in_where = in_where || is_ident(&token, "where");
+
+        if in_where {
+          where_clause.push(token.clone());
+        }
+
+        rest.push(token);
+      }
+    }
+  }
+
+  let items_group = items_group.ok_or((cursor.span(), "expected a trait body"))?;
+  let mut trait_items = TokenStream::new();
+  let mut checked_items = TokenStream::new();
+
+  for item in items(items_group.stream()) {
+    let (clauses, attrs) = item.attrs.into_iter().partition::<Vec<_>, _>(is_contract);
+
+    let mut preconditions = Vec::new();
+    let mut postconditions = Vec::new();
+
+    for clause in &clauses {
+      let args = clause
+        .args
+        .as_ref()
+        .map_or_else(Vec::new, |args| args.stream().into_iter().collect());
+      let cond = source(condition(&args));
+
+      if clause.is("requires") {
+        preconditions.push(cond);
+      } else {
+        postconditions.push(cond);
+      }
+    }
+
+    let mut docs = doc_attrs("# Preconditions", &preconditions);
+    docs.extend(doc_attrs("# Postconditions", &postconditions));
+
+    for attr in &attrs {
+      trait_items.extend(attr.tokens.clone());
+    }
+
+    trait_items.extend(docs.clone());
+    trait_items.extend(item.tokens.clone());
+    trait_items.extend(item.body.map(TokenTree::Group));
+
+    if !clauses.is_empty() {
+      let type_params = generics
+        .clone()
+        .map(Generics::new)
+        .unwrap_or_default()
+        .type_params;
+      let method = checked_method(&item.tokens, &name, type_params)?;
+      let mut method_attrs = TokenStream::new();
+
+      for attr in clauses {
+        method_attrs.extend(attr.tokens);
+      }
+
+      checked_items.extend(expand_fn(expand(
+        "$attrs $method",
+        &[("attrs", method_attrs), ("method", method)],
+      ))?);
+    }
+  }
+
+  let trait_generics = match generics {
+    Some(ref params) => expand("<$params>", &[("params", params.iter().cloned().collect())]),
+    None => TokenStream::new(),
+  };
+  let generics = generics.map(Generics::new).unwrap_or_default();
+  let checked_name = Ident::new(&format!("{}Checked", name), name.span());
+  let checked_doc = format!(
+    "Checked version of [`{}`].\n\nThe methods of this trait check the contract of the \
+     corresponding methods of [`{}`] around their implementation.",
+    name, name
+  );
+
+  let mut trait_attrs = TokenStream::new();
+
+  for attr in attrs {
+    trait_attrs.extend(attr.tokens);
+  }
+
+  Ok(expand(
+    "
+    $trait_attrs
+    $vis $header $name $trait_generics $rest { $trait_items }
+
+    #[doc = $checked_doc]
+    $vis trait $checked_name<$impl_params>: $name<$type_params> $where_clause {
+      $checked_items
+    }
+
+    impl<$impl_params __GuardT> $checked_name<$type_params> for __GuardT
+    where
+      __GuardT: ?Sized + $name<$type_params>,
+      $where_predicates
+    {
+    }
+    ",
+    &[
+      ("trait_attrs", trait_attrs),
+      ("vis", vis.iter().cloned().collect()),
+      ("header", header.into_iter().collect()),
+      ("name", TokenTree::Ident(name.clone()).into()),
+      ("trait_generics", trait_generics),
+      ("rest", rest.into_iter().collect()),
+      ("trait_items", trait_items),
+      ("checked_doc", string(&checked_doc)),
+      ("checked_name", TokenTree::Ident(checked_name).into()),
+      ("checked_items", checked_items),
+      ("impl_params", generics.impl_params.clone()),
+      ("type_params", generics.type_params.clone()),
+      ("where_clause", where_clause.iter().cloned().collect()),
+      (
+        "where_predicates",
+        where_clause.into_iter().skip(1).collect(),
+      ),
+    ],
+  ))
+}
+
+/// Checked method forwarding to the method declared by `tokens`, without its contract attributes.
+fn checked_method(
+  tokens: &[TokenTree],
+  trait_name: &Ident,
+  type_params: TokenStream,
+) -> Result<TokenStream, (Span, &'static str)> {
+  let fn_pos = tokens
+    .iter()
+    .position(|token| is_ident(token, "fn"))
+    .ok_or((Span::call_site(), "contracts can only be put on methods"))?;
+
+  let name = match tokens.get(fn_pos + 1) {
+    Some(TokenTree::Ident(ident)) => ident.clone(),
+    _ => return Err((tokens[fn_pos].span(), "expected a method name")),
+  };
+
+  let (params_pos, params) = match params_index(tokens).map(|i| (i, &tokens[i])) {
+    Some((i, TokenTree::Group(group))) => (i, group),
+    _ => return Err((name.span(), "expected method parameters")),
+  };
+
+  let mut new_params = TokenStream::new();
+  let mut args = TokenStream::new();
+  let mut by_ref = false;
+
+  for (i, param) in split_commas(params.stream().into_iter().collect())
+    .into_iter()
+    .enumerate()
+  {
+    let colon = param.iter().position(|token| is_punct(token, ':'));
+    let pat = &param[..colon.unwrap_or(param.len())];
+
+    if pat.iter().any(|token| is_ident(token, "self")) {
+      let ty = colon.map_or(&[][..], |colon| &param[colon + 1..]);
+      by_ref = pat.first().map_or(false, |token| is_punct(token, '&'))
+        || ty.first().map_or(false, |token| is_punct(token, '&'));
+      new_params.extend(expand(
+        "$param,",
+        &[("param", param.iter().cloned().collect())],
+      ));
+      args.extend(expand("self,", &[]));
+      continue;
+    }
+
+    // anonymous parameters are named, so that they can be forwarded
+    let arg = match pat {
+      [TokenTree::Ident(ident)] if ident.to_string() != "_" => ident.clone(),
+      _ => Ident::new(&format!("__guard_arg_{}", i), Span::call_site()),
+    };
+    let ty = colon.map_or(&[][..], |colon| &param[colon + 1..]);
+
+    new_params.extend(expand(
+      "$arg: $ty,",
+      &[
+        ("arg", TokenTree::Ident(arg.clone()).into()),
+        ("ty", ty.iter().cloned().collect()),
+      ],
+    ));
+    args.extend(expand("$arg,", &[("arg", TokenTree::Ident(arg).into())]));
+  }
+
+  let mut after = tokens[params_pos + 1..].to_vec();
+
+  if after.last().map_or(false, |token| is_punct(token, ';')) {
+    after.pop();
+  }
+
+  // forwarding requires Self: Sized if self is moved or returned
+  let returns_self = after
+    .windows(2)
+    .any(|w| is_ident(&w[0], "Self") && !is_punct(&w[1], ':'))
+    || after.last().map_or(false, |token| is_ident(token, "Self"));
+
+  if !by_ref || returns_self {
+    let sized = if !after.iter().any(|token| is_ident(token, "where")) {
+      "where Self: Sized"
+    } else if after.last().map_or(false, |token| is_punct(token, ',')) {
+      "Self: Sized"
+    } else {
+      ", Self: Sized"
+    };
+
+    after.extend(sized.parse::<TokenStream>().unwrap());
+  }
+
+  let doc = format!("Checked version of [`{}::{}`].", trait_name, name);
+
+  Ok(expand(
+    "
+    #[doc = $doc]
+    $before fn $checked_name $generics ($params) $after {
+      <Self as $trait_name<$type_params>>::$name($args)
+    }
+    ",
+    &[
+      ("doc", string(&doc)),
+      ("before", tokens[..fn_pos].iter().cloned().collect()),
+      (
+        "checked_name",
+        TokenTree::Ident(Ident::new(&format!("checked_{}", name), name.span())).into(),
+      ),
+      (
+        "generics",
+        tokens[fn_pos + 2..params_pos].iter().cloned().collect(),
+      ),
+      ("params", new_params),
+      ("after", after.into_iter().collect()),
+      ("trait_name", TokenTree::Ident(trait_name.clone()).into()),
+      ("type_params", type_params),
+      ("name", TokenTree::Ident(name).into()),
+      ("args", args),
+    ],
+  ))
+}
@@ -0,0 +1,356 @@
+//! `#[derive(Validate)]`.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use crate::report;
+use crate::tokens::{
+  error, expand, is_ident, is_punct, replace_self, source_text, split_commas, string, Cursor,
+  Generics,
+};
+
+/// Check to perform on a field.
+///
+/// Predicates come with their source text, used in reports: stringifying tokens that went through
+/// a procedural macro doesn’t preserve their spacing.
+enum Check {
+  /// `#[guard(pred)]`, where `self` is the field.
+  Pred(TokenStream, String),
+  /// `#[guard(range = range)]`.
+  Range(TokenStream, String),
+  /// `#[guard(nested)]`.
+  Nested,
+}
+
+struct Field {
+  /// Name of the field, as used in paths.
+  name: String,
+  /// `self.field`.
+  access: TokenStream,
+  ty: Vec<TokenTree>,
+  checks: Vec<Check>,
+}
+
+pub fn derive(input: TokenStream) -> TokenStream {
+  match parse(input) {
+    Ok(tokens) => tokens,
+    Err((span, msg)) => error(span, msg),
+  }
+}
+
+fn parse(input: TokenStream) -> Result<TokenStream, (Span, &'static str)> {
+  let mut cursor = Cursor::new(input);
+
+  cursor.attributes();
+  cursor.visibility();
+
+  if !cursor.eat_ident("struct") {
+    return Err((cursor.span(), "#[derive(Validate)] only supports structs"));
+  }
+
+  let name = match cursor.next() {
+    Some(TokenTree::Ident(ident)) => ident,
+    _ => return Err((cursor.span(), "expected a struct name")),
+  };
+
+  let generics = cursor.generics().map(Generics::new).unwrap_or_default();
+  let mut where_clause = Vec::new();
+  let mut fields = Vec::new();
+
+  while let Some(token) = cursor.next() {
+    match token {
+      TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace => {
+        fields = parse_fields(group.stream(), true)?;
+        break;
+      }
+
+      TokenTree::Group(ref group) if group.delimiter() == Delimiter::Parenthesis => {
+        fields = parse_fields(group.stream(), false)?;
+      }
+
+      ref token if is_punct(token, ';') => break,
+
+      token => where_clause.push(token),
+    }
+  }
+
+  // nested fields of generic types need the parameters they mention to implement Validate
+  let bounds = generics
+    .types
+    .iter()
+    .filter(|param| {
+      fields.iter().any(|field| {
+        field.checks.iter().any(|c| matches!(c, Check::Nested)) && mentions(&field.ty, param)
+      })
+    })
+    .flat_map(|param| {
+      expand(
+        "$param: ::try_guard::Validate,",
+        &[("param", TokenTree::Ident(param.clone()).into())],
+      )
+    })
+    .collect::<Vec<_>>();
+
+  if !bounds.is_empty() {
+    match where_clause.last() {
+      None => where_clause.extend(expand("where", &[])),
+      Some(token) if !is_punct(token, ',') => {
+        where_clause.push(Punct::new(',', Spacing::Alone).into())
+      }
+      _ => (),
+    }
+
+    where_clause.extend(bounds);
+  }
+
+  let mut short = TokenStream::new();
+  let mut accumulate = TokenStream::new();
+
+  for field in fields.iter().filter(|field| !field.checks.is_empty()) {
+    short.extend(short_circuit(field));
+    accumulate.extend(accumulate_all(field));
+  }
+
+  Ok(expand(
+    "
+    impl<$impl_params> ::try_guard::Validate for $name<$type_params> $where_clause {
+      fn validate(&self) -> ::core::result::Result<(), ::try_guard::GuardError> {
+        $short
+        ::core::result::Result::Ok(())
+      }
+
+      fn validate_in(&self, __guard_ctx: &mut ::try_guard::ValidationContext) {
+        $accumulate
+      }
+    }
+    ",
+    &[
+      ("impl_params", generics.impl_params),
+      ("name", TokenTree::Ident(name).into()),
+      ("type_params", generics.type_params),
+      ("where_clause", where_clause.into_iter().collect()),
+      ("short", short),
+      ("accumulate", accumulate),
+    ],
+  ))
+}
+
+fn parse_fields(stream: TokenStream, named: bool) -> Result<Vec<Field>, (Span, &'static str)> {
+  let mut fields = Vec::new();
+
+  for (index, tokens) in split_commas(stream.into_iter().collect())
+    .into_iter()
+    .enumerate()
+  {
+    let mut cursor = Cursor::new(tokens.into_iter().collect());
+    let mut checks = Vec::new();
+
+    for attr in cursor.attributes() {
+      if attr.path != "guard" {
+        continue;
+      }
+
+      match attr.args {
+        Some(args) => checks.push(parse_check(args)?),
+        None => return Err((attr.tokens[0].span(), "expected #[guard(…)]")),
+      }
+    }
+
+    cursor.visibility();
+
+    let member = if named {
+      let member = match cursor.next() {
+        Some(TokenTree::Ident(ident)) => TokenTree::Ident(ident),
+        _ => return Err((cursor.span(), "expected a field name")),
+      };
+
+      if !cursor.eat_punct(':') {
+        return Err((cursor.span(), "expected a field type"));
+      }
+
+      member
+    } else {
+      TokenTree::Literal(Literal::usize_unsuffixed(index))
+    };
+
+    let ty = std::iter::from_fn(|| cursor.next()).collect();
+
+    let name = member.to_string();
+    let name = name.trim_start_matches("r#").to_owned();
+    let access = expand("self.$member", &[("member", member.into())]);
+
+    fields.push(Field {
+      name,
+      access,
+      ty,
+      checks,
+    });
+  }
+
+  Ok(fields)
+}
+
+fn parse_check(args: Group) -> Result<Check, (Span, &'static str)> {
+  let tokens = args.stream().into_iter().collect::<Vec<_>>();
+  let text = source_text(args.span());
+  let text = |tokens: &[TokenTree], prefix: &str| {
+    text
+      .as_ref()
+      .and_then(|text| text[1..text.len() - 1].trim().strip_prefix(prefix))
+      .map(|text| text.trim().to_owned())
+      .unwrap_or_else(|| tokens.iter().cloned().collect::<TokenStream>().to_string())
+  };
+
+  match tokens.as_slice() {
+    [] => Err((args.span(), "expected a predicate, range = … or nested")),
+    [ident] if is_ident(ident, "nested") => Ok(Check::Nested),
+    [ident, eq, range @ ..] if is_ident(ident, "range") && is_punct(eq, '=') => {
+      if range.is_empty() {
+        Err((eq.span(), "expected a range"))
+      } else {
+        let text = text(range, "range")
+          .trim_start_matches('=')
+          .trim()
+          .to_owned();
+        Ok(Check::Range(range.iter().cloned().collect(), text))
+      }
+    }
+    _ => Ok(Check::Pred(
+      tokens.iter().cloned().collect(),
+      text(&tokens, ""),
+    )),
+  }
+}
+
+/// Whether `ident` appears in `tokens`, recursively.
+fn mentions(tokens: &[TokenTree], ident: &Ident) -> bool {
+  let name = ident.to_string();
+
+  tokens.iter().any(|token| match token {
+    TokenTree::Ident(other) => other.to_string() == name,
+    TokenTree::Group(group) => mentions(&group.stream().into_iter().collect::<Vec<_>>(), ident),
+    _ => false,
+  })
+}
+
+/// Replace every `self` identifier with `access` in the source text of a predicate.
+fn replace_self_text(text: &str, access: &str) -> String {
+  let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+  let mut out = String::new();
+  let mut rest = text;
+  let mut in_str = false;
+
+  while let Some(c) = rest.chars().next() {
+    if in_str {
+      if c == '\\' {
+        let escaped = rest.chars().nth(1).map_or(1, |c| 1 + c.len_utf8());
+        out.push_str(&rest[..escaped]);
+        rest = &rest[escaped..];
+        continue;
+      }
+
+      in_str = c != '"';
+    } else if c == '"' {
+      in_str = true;
+    } else if rest.starts_with("self")
+      && !out.ends_with(is_ident)
+      && !rest[4..].starts_with(is_ident)
+    {
+      out.push_str(access);
+      rest = &rest[4..];
+      continue;
+    }
+
+    out.push(c);
+    rest = &rest[c.len_utf8()..];
+  }
+
+  out
+}
+
+fn segment(field: &Field) -> TokenStream {
+  expand(
+    "::try_guard::PathSegment::Field(::std::borrow::Cow::Borrowed($name))",
+    &[("name", string(&field.name))],
+  )
+}
+
+/// Check of a field, as an expression of type `Result<(), GuardError>`.
+///
+/// Nested checks are only supported in short-circuit mode, where they simply call
+/// `Validate::validate`.
+fn check(field: &Field, check: &Check) -> TokenStream {
+  let access = field.access.to_string().replace(' ', "");
+
+  match check {
+    Check::Pred(pred, text) => report::check(
+      replace_self(pred.clone(), &field.access),
+      &replace_self_text(text, &access),
+    ),
+
+    Check::Range(range, text) => expand(
+      "
+      if ($range).contains(&$access) {
+        ::core::result::Result::Ok(())
+      } else {
+        ::core::result::Result::Err(
+          ::try_guard::GuardError::new($expr, file!(), line!(), column!(), module_path!())
+            .with_operand($access_text, ::try_guard::__guard_capture!(&$access)),
+        )
+      }
+      ",
+      &[
+        ("range", range.clone()),
+        ("access", field.access.clone()),
+        ("access_text", string(&access)),
+        ("expr", string(&format!("({}).contains(&{})", text, access))),
+      ],
+    ),
+
+    Check::Nested => expand(
+      "::try_guard::Validate::validate(&$access)",
+      &[("access", field.access.clone())],
+    ),
+  }
+}
+
+/// Short-circuiting checks of a field, for `Validate::validate`.
+fn short_circuit(field: &Field) -> TokenStream {
+  field
+    .checks
+    .iter()
+    .flat_map(|c| {
+      expand(
+        "$check.map_err(|error| ::try_guard::PrefixPath::prefix_path(error, &[$segment]))?;",
+        &[("check", check(field, c)), ("segment", segment(field))],
+      )
+    })
+    .collect()
+}
+
+/// Accumulating checks of a field, for `Validate::validate_in`.
+fn accumulate_all(field: &Field) -> TokenStream {
+  let checks = field
+    .checks
+    .iter()
+    .flat_map(|c| match c {
+      Check::Nested => expand(
+        "::try_guard::Validate::validate_in(&$access, __guard_ctx);",
+        &[("access", field.access.clone())],
+      ),
+
+      _ => expand(
+        "
+        if let ::core::result::Result::Err(error) = $check {
+          __guard_ctx.fail(error);
+        }
+        ",
+        &[("check", check(field, c))],
+      ),
+    })
+    .collect();
+
+  expand(
+    "__guard_ctx.field($name, |__guard_ctx| { $checks });",
+    &[("name", string(&field.name)), ("checks", checks)],
+  )
+}